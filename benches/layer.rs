@@ -0,0 +1,75 @@
+use std::{
+    convert::Infallible,
+    future::{ready, Future, Ready},
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axum_cc::{CacheControlLayer, MimeType};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use http::{header::CONTENT_TYPE, HeaderValue, Request, Response};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// An inner service that immediately responds with a CSS body.
+#[derive(Clone)]
+struct Css;
+
+impl Service<Request<()>> for Css {
+    type Response = Response<()>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Request<()>) -> Self::Future {
+        let mut response = Response::new(());
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("text/css"));
+
+        ready(Ok(response))
+    }
+}
+
+fn call<S>(service: &mut S) -> Response<()>
+where
+    S: Service<Request<()>, Response = Response<()>, Error = Infallible>,
+{
+    let mut cx = Context::from_waker(Waker::noop());
+    let future = pin!(service.call(Request::new(())));
+
+    match future.poll(&mut cx) {
+        Poll::Ready(Ok(response)) => response,
+        Poll::Pending => unreachable!("inner service is always ready"),
+    }
+}
+
+fn layer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CacheControl");
+
+    let mut inner = Css;
+    group.bench_function("inner service", |b| b.iter(|| black_box(call(&mut inner))));
+
+    let mut empty = CacheControlLayer::default().layer(Css);
+    group.bench_function("empty layer", |b| b.iter(|| black_box(call(&mut empty))));
+
+    let mut unmatched = CacheControlLayer::new()
+        .with_mime_types(&[MimeType::PNG])
+        .layer(Css);
+    group.bench_function("unmatched layer", |b| {
+        b.iter(|| black_box(call(&mut unmatched)))
+    });
+
+    let mut matched = CacheControlLayer::new().layer(Css);
+    group.bench_function("matched layer", |b| {
+        b.iter(|| black_box(call(&mut matched)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, layer);
+criterion_main!(benches);
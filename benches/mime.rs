@@ -0,0 +1,22 @@
+use axum_cc::MimeType;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use http::HeaderValue;
+
+fn from_header_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("MimeType::try_from(&HeaderValue)");
+
+    for value in [
+        "text/css",
+        "application/javascript",
+        "text/html; charset=utf-8",
+        "application/octet-stream",
+    ] {
+        let header = HeaderValue::from_static(value);
+        group.bench_function(value, |b| b.iter(|| MimeType::try_from(black_box(&header))));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, from_header_value);
+criterion_main!(benches);
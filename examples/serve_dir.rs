@@ -0,0 +1,42 @@
+//! Serves the `examples/assets` directory with long-lived `Cache-Control`
+//! headers on CSS, JS, and image files.
+//!
+//! ```sh
+//! cargo run --example serve_dir
+//! curl -I http://127.0.0.1:3000/assets/style.css
+//! ```
+
+use std::time::Duration;
+
+use axum::Router;
+use axum_cc::{CacheControlLayer, MimeType};
+use tower_http::services::ServeDir;
+
+const MIME_TYPES: [MimeType; 3] = [MimeType::CSS, MimeType::JS, MimeType::PNG];
+
+#[tokio::main]
+async fn main() {
+    // The layer must wrap `ServeDir` (here, by being applied to the router
+    // after the service is nested) so that it sees the `Content-Type` and
+    // `Accept-Ranges` headers that `ServeDir` sets on the files it serves.
+    // Responses from the fallback (e.g. a 404) don't carry `Accept-Ranges`,
+    // so they are left untouched.
+    //
+    // If you also compress responses, add the compression layer *before*
+    // this one (so it sits closer to `ServeDir`); the compressed response
+    // still carries the original `Content-Type`.
+    let cache_control = CacheControlLayer::new()
+        .with_mime_types(&MIME_TYPES)
+        .with_max_age(Duration::from_secs(60 * 60 * 24 * 30))
+        .with_marker_header("accept-ranges");
+
+    let app = Router::new()
+        .nest_service("/assets", ServeDir::new("examples/assets"))
+        .layer(cache_control);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .unwrap();
+
+    axum::serve(listener, app).await.unwrap();
+}
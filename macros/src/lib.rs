@@ -0,0 +1,311 @@
+//! Attribute macros for [`axum-cc`](https://docs.rs/axum-cc).
+//!
+//! Use them through `axum-cc` with its `macros` feature enabled, rather than
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Error, Expr, ExprLit, FnArg,
+    ItemFn, Lit, Meta, PatType, ReturnType, Token,
+};
+
+/// The largest lifetime caches are required to handle, in seconds.
+const MAX_DELTA_SECONDS: u64 = 1 << 31;
+
+/// Set `Cache-Control` on the responses of an axum handler.
+///
+/// See the documentation of `axum_cc::cache_control` for the directives it
+/// accepts.
+#[proc_macro_attribute]
+pub fn cache_control(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args with Punctuated::<Meta, Token![,]>::parse_terminated);
+    let item = parse_macro_input!(item as ItemFn);
+
+    expand(args, item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(args: Punctuated<Meta, Token![,]>, item: ItemFn) -> syn::Result<TokenStream2> {
+    let directives = Directives::parse(args)?;
+    let directives = directives.to_tokens();
+
+    if item.sig.asyncness.is_none() {
+        return Err(Error::new(
+            item.sig.fn_token.span(),
+            "`cache_control` only applies to `async fn` handlers",
+        ));
+    }
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+
+    let mut outer = sig.clone();
+    let mut inner = sig;
+    inner.ident = format_ident!("__axum_cc_{}", inner.ident);
+
+    let mut args = Vec::new();
+
+    for (i, arg) in outer.inputs.iter_mut().enumerate() {
+        match arg {
+            FnArg::Typed(PatType { pat, .. }) => {
+                let ident = format_ident!("__arg{}", i);
+                **pat = syn::parse_quote!(#ident);
+                args.push(ident);
+            }
+            FnArg::Receiver(receiver) => {
+                return Err(Error::new(
+                    receiver.span(),
+                    "`cache_control` doesn't apply to methods",
+                ));
+            }
+        }
+    }
+
+    let output = match &inner.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    outer.output = syn::parse_quote!(-> ::axum_cc::Cached<#output>);
+
+    let ident = &inner.ident;
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #outer {
+            #inner #block
+
+            ::axum_cc::Cached::new(#directives, #ident(#(#args),*).await)
+        }
+    })
+}
+
+/// The directives given to the attribute.
+#[derive(Default)]
+struct Directives {
+    public: bool,
+    private: bool,
+    no_cache: bool,
+    no_store: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+    must_revalidate: bool,
+    proxy_revalidate: bool,
+    no_transform: bool,
+    immutable: bool,
+    stale_while_revalidate: Option<u64>,
+    stale_if_error: Option<u64>,
+}
+
+impl Directives {
+    fn parse(args: Punctuated<Meta, Token![,]>) -> syn::Result<Self> {
+        if args.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "expected at least one directive",
+            ));
+        }
+
+        let mut directives = Self::default();
+
+        for meta in &args {
+            let name = meta
+                .path()
+                .get_ident()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+
+            match meta {
+                Meta::Path(_) => {
+                    let flag = match name.as_str() {
+                        "public" => &mut directives.public,
+                        "private" => &mut directives.private,
+                        "no_cache" => &mut directives.no_cache,
+                        "no_store" => &mut directives.no_store,
+                        "must_revalidate" => &mut directives.must_revalidate,
+                        "proxy_revalidate" => &mut directives.proxy_revalidate,
+                        "no_transform" => &mut directives.no_transform,
+                        "immutable" => &mut directives.immutable,
+                        _ => return Err(Error::new(meta.span(), "unknown directive")),
+                    };
+
+                    *flag = true;
+                }
+                Meta::NameValue(nv) => {
+                    let lifetime = match name.as_str() {
+                        "max_age" => &mut directives.max_age,
+                        "s_maxage" => &mut directives.s_maxage,
+                        "stale_while_revalidate" => &mut directives.stale_while_revalidate,
+                        "stale_if_error" => &mut directives.stale_if_error,
+                        _ => return Err(Error::new(meta.span(), "unknown directive")),
+                    };
+
+                    *lifetime = Some(seconds(&nv.value)?);
+                }
+                Meta::List(_) => return Err(Error::new(meta.span(), "unknown directive")),
+            }
+        }
+
+        directives.validate(args.span())?;
+        Ok(directives)
+    }
+
+    /// Reject combinations that a `CacheControlDirectives` would reject too.
+    fn validate(&self, span: Span) -> syn::Result<()> {
+        let has_lifetime = self.max_age.is_some()
+            || self.s_maxage.is_some()
+            || self.stale_while_revalidate.is_some()
+            || self.stale_if_error.is_some();
+
+        let conflict = if self.public && self.private {
+            Some("`public` conflicts with `private`")
+        } else if self.no_store && (self.public || has_lifetime || self.immutable) {
+            Some("`no_store` conflicts with directives that allow caching")
+        } else if self.immutable && self.no_cache {
+            Some("`immutable` conflicts with `no_cache`")
+        } else if self.private && self.s_maxage.is_some() {
+            Some("`s_maxage` has no effect on `private` responses")
+        } else {
+            None
+        };
+
+        match conflict {
+            Some(message) => Err(Error::new(span, message)),
+            None => Ok(()),
+        }
+    }
+
+    fn to_tokens(&self) -> TokenStream2 {
+        let duration = |secs: u64| quote!(::core::time::Duration::from_secs(#secs));
+        let mut tokens = quote!(::axum_cc::CacheControlDirectives::new());
+
+        if self.public {
+            tokens.extend(quote!(.public()));
+        }
+
+        if self.private {
+            tokens.extend(quote!(.private()));
+        }
+
+        if self.no_cache {
+            tokens.extend(quote!(.no_cache()));
+        }
+
+        if self.no_store {
+            tokens.extend(quote!(.no_store()));
+        }
+
+        if let Some(secs) = self.max_age.map(duration) {
+            tokens.extend(quote!(.with_max_age(#secs)));
+        }
+
+        if let Some(secs) = self.s_maxage.map(duration) {
+            tokens.extend(quote!(.with_s_maxage(#secs)));
+        }
+
+        if self.must_revalidate {
+            tokens.extend(quote!(.must_revalidate()));
+        }
+
+        if self.proxy_revalidate {
+            tokens.extend(quote!(.proxy_revalidate()));
+        }
+
+        if self.no_transform {
+            tokens.extend(quote!(.no_transform()));
+        }
+
+        if self.immutable {
+            tokens.extend(quote!(.immutable()));
+        }
+
+        if let Some(secs) = self.stale_while_revalidate.map(duration) {
+            tokens.extend(quote!(.with_stale_while_revalidate(#secs)));
+        }
+
+        if let Some(secs) = self.stale_if_error.map(duration) {
+            tokens.extend(quote!(.with_stale_if_error(#secs)));
+        }
+
+        tokens
+    }
+}
+
+/// A lifetime, given as a number of seconds or a duration string.
+fn seconds(expr: &Expr) -> syn::Result<u64> {
+    let secs = match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse()?,
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => parse_duration(&s.value()).ok_or_else(|| {
+            Error::new(
+                s.span(),
+                "invalid duration, expected something like \"1h30m\"",
+            )
+        })?,
+        _ => {
+            return Err(Error::new(
+                expr.span(),
+                "expected a number of seconds or a duration string",
+            ))
+        }
+    };
+
+    if secs > MAX_DELTA_SECONDS {
+        return Err(Error::new(
+            expr.span(),
+            "lifetimes above 2^31 seconds aren't supported by every cache",
+        ));
+    }
+
+    Ok(secs)
+}
+
+/// Parse a duration like `axum_cc::parse_duration` does, in seconds.
+fn parse_duration(s: &str) -> Option<u64> {
+    let s = s.trim();
+
+    if let Ok(secs) = s.parse() {
+        return Some(secs);
+    }
+
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut total: u64 = 0;
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let (number, tail) = rest.split_at(digits);
+        let number: u64 = number.parse().ok()?;
+
+        let tail = tail.trim_start();
+        let unit = tail.bytes().take_while(u8::is_ascii_alphabetic).count();
+        let (unit, tail) = tail.split_at(unit);
+
+        let secs = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 60 * 60 * 24,
+            "w" => 60 * 60 * 24 * 7,
+            "y" => 60 * 60 * 24 * 365,
+            _ => return None,
+        };
+
+        total = total.checked_add(number.checked_mul(secs)?)?;
+        rest = tail.trim_start();
+    }
+
+    Some(total)
+}
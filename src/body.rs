@@ -0,0 +1,228 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use futures_core::ready;
+#[cfg(feature = "conditional")]
+use http::{
+    header::{CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, TRANSFER_ENCODING},
+    response, Response, StatusCode,
+};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+
+/// A response passed on by a layer, along with the status the inner service
+/// responded with.
+#[cfg(feature = "conditional")]
+pub(crate) type Forwarded<B> = (Response<BufferedBody<B>>, StatusCode);
+
+/// The start of a body, read ahead of time so a layer can inspect it before
+/// passing the response on.
+pub(crate) struct Buffer<B: Body> {
+    body: Pin<Box<B>>,
+    frames: VecDeque<Frame<Bytes>>,
+    /// The number of data bytes in `frames`.
+    len: usize,
+    error: Option<B::Error>,
+    ended: bool,
+}
+
+impl<B: Body> Buffer<B> {
+    pub(crate) fn new(body: B) -> Self {
+        Self {
+            body: Box::pin(body),
+            frames: VecDeque::new(),
+            len: 0,
+            error: None,
+            ended: false,
+        }
+    }
+
+    /// Read frames until at least `limit` bytes are buffered, or the body
+    /// ends or fails.
+    pub(crate) fn poll_fill(&mut self, cx: &mut Context<'_>, limit: usize) -> Poll<()> {
+        while self.len < limit && !self.ended && self.error.is_none() {
+            match ready!(self.body.as_mut().poll_frame(cx)) {
+                Some(Ok(frame)) => {
+                    let frame = frame.map_data(into_bytes);
+
+                    if let Some(data) = frame.data_ref() {
+                        self.len += data.len();
+                    }
+
+                    self.frames.push_back(frame);
+                }
+                Some(Err(error)) => self.error = Some(error),
+                None => self.ended = true,
+            }
+        }
+
+        Poll::Ready(())
+    }
+
+    /// The data buffered so far.
+    pub(crate) fn data(&self) -> impl Iterator<Item = &Bytes> {
+        self.frames.iter().filter_map(Frame::data_ref)
+    }
+
+    /// Returns `true` if the whole body is buffered.
+    #[cfg(feature = "conditional")]
+    pub(crate) fn is_complete(&self) -> bool {
+        self.ended && self.error.is_none()
+    }
+
+    /// Drop the body, leaving nothing to replay.
+    #[cfg(feature = "conditional")]
+    pub(crate) fn discard(&mut self) {
+        self.frames.clear();
+        self.len = 0;
+        self.error = None;
+        self.ended = true;
+    }
+
+    pub(crate) fn into_body(self) -> BufferedBody<B> {
+        BufferedBody {
+            inner: Inner::Buffered { buffer: self },
+        }
+    }
+}
+
+/// Turn a response into an empty `304 Not Modified`, keeping the headers a
+/// cache needs to update its stored response.
+#[cfg(feature = "conditional")]
+pub(crate) fn not_modified<B: Body>(
+    mut parts: response::Parts,
+    mut buffer: Buffer<B>,
+) -> Response<BufferedBody<B>> {
+    parts.status = StatusCode::NOT_MODIFIED;
+
+    for name in [
+        CONTENT_LENGTH,
+        CONTENT_RANGE,
+        CONTENT_TYPE,
+        TRANSFER_ENCODING,
+    ] {
+        parts.headers.remove(name);
+    }
+
+    buffer.discard();
+    Response::from_parts(parts, buffer.into_body())
+}
+
+fn into_bytes(mut data: impl Buf) -> Bytes {
+    data.copy_to_bytes(data.remaining())
+}
+
+pin_project! {
+    #[project = InnerProj]
+    enum Inner<B>
+    where
+        B: Body,
+    {
+        Direct {
+            #[pin]
+            body: B,
+        },
+        Buffered {
+            buffer: Buffer<B>,
+        },
+    }
+}
+
+pin_project! {
+    /// The body of a response passed through a layer that may read ahead,
+    /// replaying whatever was read before the rest of the body.
+    pub struct BufferedBody<B>
+    where
+        B: Body,
+    {
+        #[pin]
+        inner: Inner<B>,
+    }
+}
+
+impl<B: Body> BufferedBody<B> {
+    /// Pass `body` through untouched.
+    pub(crate) fn new(body: B) -> Self {
+        Self {
+            inner: Inner::Direct { body },
+        }
+    }
+}
+
+impl<B: Body> Body for BufferedBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match self.project().inner.project() {
+            InnerProj::Direct { body } => body
+                .poll_frame(cx)
+                .map_ok(|frame| frame.map_data(into_bytes)),
+            InnerProj::Buffered { buffer } => {
+                if let Some(frame) = buffer.frames.pop_front() {
+                    if let Some(data) = frame.data_ref() {
+                        buffer.len -= data.len();
+                    }
+
+                    return Poll::Ready(Some(Ok(frame)));
+                }
+
+                if let Some(error) = buffer.error.take() {
+                    buffer.ended = true;
+                    return Poll::Ready(Some(Err(error)));
+                }
+
+                if buffer.ended {
+                    return Poll::Ready(None);
+                }
+
+                buffer
+                    .body
+                    .as_mut()
+                    .poll_frame(cx)
+                    .map_ok(|frame| frame.map_data(into_bytes))
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.inner {
+            Inner::Direct { body } => body.is_end_stream(),
+            Inner::Buffered { buffer } => {
+                buffer.frames.is_empty()
+                    && buffer.error.is_none()
+                    && (buffer.ended || buffer.body.is_end_stream())
+            }
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.inner {
+            Inner::Direct { body } => body.size_hint(),
+            Inner::Buffered { buffer } => {
+                let rest = if buffer.ended {
+                    SizeHint::with_exact(0)
+                } else {
+                    buffer.body.size_hint()
+                };
+
+                let len = buffer.len as u64;
+                let mut hint = SizeHint::new();
+                hint.set_lower(rest.lower() + len);
+
+                if let Some(upper) = rest.upper() {
+                    hint.set_upper(upper + len);
+                }
+
+                hint
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+static CACHE_STATUS: HeaderName = HeaderName::from_static("cache-status");
+
+/// Add an entry to a response's `Cache-Status` header (RFC 9211), after the
+/// entries of any caches closer to the origin.
+///
+/// The layers that report here never store responses, so every request is
+/// forwarded: with `fwd=method` if the layer doesn't handle the request's
+/// method, `fwd=uri-miss` otherwise. `fwd_status` is the status the inner
+/// service responded with, which differs from the response's if the layer
+/// replaced it, and `detail` says why.
+pub(crate) fn append(
+    headers: &mut HeaderMap,
+    handled: bool,
+    fwd_status: StatusCode,
+    detail: Option<&str>,
+) {
+    let fwd = if handled { "uri-miss" } else { "method" };
+    let mut entry = format!("axum-cc; fwd={fwd}; fwd-status={}", fwd_status.as_u16());
+
+    if let Some(detail) = detail {
+        entry.push_str("; detail=");
+        entry.push_str(detail);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&entry) {
+        headers.append(CACHE_STATUS.clone(), value);
+    }
+}
@@ -0,0 +1,224 @@
+//! `Clear-Site-Data` headers, for telling browsers to drop what they stored.
+//!
+//! Where `Cache-Control` says how long a response may be kept,
+//! `Clear-Site-Data` asks the browser to throw away what it already has,
+//! for example when a user logs out or after a broken deploy.
+//! [`ClearSiteDataLayer`] emits it on responses picked by the same
+//! [`Matcher`]s a [`CacheControlLayer`] uses:
+//!
+//! ```rust
+//! use axum::{routing::post, Router};
+//! use axum_cc::{
+//!     clear_site_data::{ClearSiteDataLayer, SiteData},
+//!     Matcher,
+//! };
+//! use http::Method;
+//!
+//! // Clear-Site-Data: "cache", "cookies", "storage"
+//! let app: Router = Router::new()
+//!     .route("/logout", post(|| async { "bye" }))
+//!     .layer(ClearSiteDataLayer::new().rule(
+//!         Matcher::path_prefix("/logout").with_method(Method::POST),
+//!         &[SiteData::Cache, SiteData::Cookies, SiteData::Storage],
+//!     ));
+//! ```
+//!
+//! Browsers only honor the header on responses delivered over HTTPS.
+//!
+//! [`CacheControlLayer`]: crate::CacheControlLayer
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::response::Response;
+use futures_core::ready;
+use http::{header::CONTENT_TYPE, HeaderName, HeaderValue, Method, Request};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{mime::MimeType, path, rule::MatchContext, Matcher};
+
+static CLEAR_SITE_DATA: HeaderName = HeaderName::from_static("clear-site-data");
+
+/// A kind of data stored by the browser for a site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SiteData {
+    /// `"cache"`: cached responses, including those in the back/forward
+    /// cache.
+    Cache,
+    /// `"cookies"`: cookies, along with HTTP authentication credentials.
+    Cookies,
+    /// `"storage"`: DOM storage such as `localStorage` and IndexedDB, and
+    /// service worker registrations.
+    Storage,
+    /// `"executionContexts"`: reload every open browsing context of the
+    /// site. Not supported by every browser.
+    ExecutionContexts,
+    /// `"*"`: all of the above, and any types added to the spec later.
+    All,
+}
+
+impl SiteData {
+    /// The quoted string this type is written as in the header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SiteData::Cache => "\"cache\"",
+            SiteData::Cookies => "\"cookies\"",
+            SiteData::Storage => "\"storage\"",
+            SiteData::ExecutionContexts => "\"executionContexts\"",
+            SiteData::All => "\"*\"",
+        }
+    }
+}
+
+/// A layer that sets `Clear-Site-Data` on responses matched by its rules.
+///
+/// Rules are checked in the order they were added, and the first match
+/// wins. Unlike a `CacheControlLayer`, the layer applies to requests with
+/// any method, since logout endpoints are usually `POST`s. Responses that
+/// already carry `Clear-Site-Data` are left untouched.
+#[derive(Debug, Clone, Default)]
+pub struct ClearSiteDataLayer {
+    rules: Vec<(Matcher, HeaderValue)>,
+}
+
+impl ClearSiteDataLayer {
+    /// Create a new `ClearSiteDataLayer` without rules, which leaves every
+    /// response untouched.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clear `data` when a response matches `matcher`.
+    ///
+    /// Rules without any data are ignored. To only clear data once a logout
+    /// succeeded, add a status condition:
+    ///
+    /// ```rust
+    /// use axum_cc::{clear_site_data::{ClearSiteDataLayer, SiteData}, Matcher};
+    ///
+    /// let layer = ClearSiteDataLayer::new().rule(
+    ///     Matcher::path_prefix("/logout").with_status_range(200..300),
+    ///     &[SiteData::All],
+    /// );
+    /// ```
+    pub fn rule(mut self, matcher: Matcher, data: &[SiteData]) -> Self {
+        if data.is_empty() {
+            return self;
+        }
+
+        let value = data
+            .iter()
+            .map(SiteData::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            self.rules.push((matcher, value));
+        }
+
+        self
+    }
+
+    /// Returns `true` if any rule needs the request path.
+    fn uses_path(&self) -> bool {
+        self.rules.iter().any(|(matcher, _)| matcher.uses_path())
+    }
+}
+
+impl<S> Layer<S> for ClearSiteDataLayer {
+    type Service = ClearSiteData<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClearSiteData {
+            inner,
+            layer: Arc::new(self.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClearSiteData<S> {
+    inner: S,
+    layer: Arc<ClearSiteDataLayer>,
+}
+
+impl<S, T, B> Service<Request<T>> for ClearSiteData<S>
+where
+    S: Service<Request<T>, Response = Response<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        let path = self
+            .layer
+            .uses_path()
+            .then(|| path::normalize(req.uri().path()));
+
+        ResponseFuture {
+            method: req.method().clone(),
+            path,
+            response_future: self.inner.call(req),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+pin_project! {
+    pub struct ResponseFuture<F> {
+        #[pin]
+        response_future: F,
+        layer: Arc<ClearSiteDataLayer>,
+        method: Method,
+        path: Option<String>,
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut response = ready!(this.response_future.poll(cx))?;
+
+        if this.layer.rules.is_empty() || response.headers().contains_key(&CLEAR_SITE_DATA) {
+            return Poll::Ready(Ok(response));
+        }
+
+        let mime = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| MimeType::try_from(value).ok());
+
+        let cx = MatchContext {
+            method: this.method,
+            path: this.path.as_deref(),
+            mime: mime.as_ref(),
+            status: response.status(),
+            content_length: MatchContext::content_length(response.headers()),
+            attachment: MatchContext::attachment(response.headers()),
+        };
+
+        if let Some((_, value)) = this.layer.rules.iter().find(|(m, _)| m.matches(&cx)) {
+            response
+                .headers_mut()
+                .insert(CLEAR_SITE_DATA.clone(), value.clone());
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
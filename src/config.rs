@@ -0,0 +1,363 @@
+//! Layer configuration that can be loaded from files.
+//!
+//! [`CacheControlConfig`] deserializes from any format serde supports, such
+//! as TOML, YAML, or JSON, so caching policy can live next to the rest of a
+//! deployment's configuration. [`CacheControlLayer::from_config`] turns it
+//! into a layer:
+//!
+//! ```rust
+//! use axum_cc::{config::CacheControlConfig, CacheControlLayer};
+//!
+//! let config: CacheControlConfig = serde_json::from_str(r#"{
+//!     "mime-types": ["text/css", "application/javascript"],
+//!     "directives": { "visibility": "public", "max-age": "30d" },
+//!     "policies": [
+//!         { "mime": "text/html", "directives": { "no-cache": true } }
+//!     ],
+//!     "rules": [
+//!         {
+//!             "path-prefix": "/assets",
+//!             "directives": { "visibility": "public", "max-age": "1y", "immutable": true }
+//!         }
+//!     ]
+//! }"#)?;
+//!
+//! let layer = CacheControlLayer::from_config(config);
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+//!
+//! Keys are written in kebab-case, like the directives they configure.
+//! Durations are either a number of seconds or a string like `"1h30m"`, as
+//! accepted by [`parse_duration`]. Unknown keys are rejected, so typos don't
+//! go unnoticed.
+//!
+//! [`CacheControlLayer::from_config`]: crate::CacheControlLayer::from_config
+//! [`parse_duration`]: crate::parse_duration
+
+use std::{fmt, time::Duration};
+
+use http::{HeaderName, Method, StatusCode};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::{
+    directives::{CacheControlDirectives, Visibility},
+    duration::parse_duration,
+    layer::{Downgrade, OverwriteBehavior, RangePolicy},
+    mime::MimeType,
+    rule::Matcher,
+};
+
+/// The configuration of a [`CacheControlLayer`], as loaded from a file.
+///
+/// Every field is optional; anything left out keeps the default of
+/// [`CacheControlLayer::new`].
+///
+/// [`CacheControlLayer`]: crate::CacheControlLayer
+/// [`CacheControlLayer::new`]: crate::CacheControlLayer::new
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CacheControlConfig {
+    /// The MIME types that get the layer's directives.
+    pub mime_types: Option<Vec<MimeType>>,
+    /// The directives for responses with one of the MIME types.
+    pub directives: Option<CacheControlDirectives>,
+    /// Directives for specific MIME types.
+    pub policies: Vec<PolicyConfig>,
+    /// Rules, checked in order before the MIME types.
+    #[serde(deserialize_with = "rules")]
+    pub rules: Vec<RuleConfig>,
+    /// The directives for responses nothing else matches.
+    pub default_policy: Option<CacheControlDirectives>,
+    /// The directives for redirects.
+    pub redirects: Option<RedirectConfig>,
+    /// The request methods the layer applies to.
+    #[serde(deserialize_with = "methods")]
+    pub methods: Option<Vec<Method>>,
+    /// Request headers to add to `Vary`.
+    #[serde(deserialize_with = "header_names")]
+    pub vary: Vec<HeaderName>,
+    pub overwrite: Option<OverwriteBehavior>,
+    pub range_policy: Option<RangePolicy>,
+    pub set_cookie_downgrade: Option<Downgrade>,
+    pub authorization_downgrade: Option<Downgrade>,
+    pub error_downgrade: Option<Downgrade>,
+    pub attachment_downgrade: Option<Downgrade>,
+    /// Never emit a `max-age` or `s-maxage` above this.
+    #[serde(deserialize_with = "optional_duration")]
+    pub max_age_ceiling: Option<Duration>,
+    /// Cache fingerprinted file names as `immutable`.
+    pub fingerprinting: bool,
+    /// Query parameters that mark a URL as immutable, like `v` in `?v=3`.
+    pub version_params: Vec<String>,
+    pub expires: bool,
+    pub pragma: bool,
+    pub dry_run: bool,
+    /// Name the rule behind each response's policy in a header.
+    pub rule_header: bool,
+}
+
+/// Directives for responses with a specific MIME type.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyConfig {
+    pub mime: MimeType,
+    pub directives: CacheControlDirectives,
+}
+
+/// Directives for permanent and temporary redirects.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedirectConfig {
+    pub permanent: CacheControlDirectives,
+    pub temporary: CacheControlDirectives,
+}
+
+/// A rule: the conditions of a [`Matcher`], and the directives to emit
+/// when they hold.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RuleConfig {
+    /// The rule's name, for the rule header.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Only match paths under this prefix. Can't be combined with
+    /// `path-glob`.
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Only match paths matching this glob. Can't be combined with
+    /// `path-prefix`.
+    #[serde(default)]
+    pub path_glob: Option<String>,
+    #[serde(default, deserialize_with = "methods")]
+    pub methods: Option<Vec<Method>>,
+    #[serde(default)]
+    pub mime_types: Vec<MimeType>,
+    /// MIME type families, such as `image/*`.
+    #[serde(default)]
+    pub mime_families: Vec<String>,
+    /// Only match fingerprinted file names.
+    #[serde(default)]
+    pub fingerprinted: bool,
+    /// Only match `Content-Disposition: attachment` responses.
+    #[serde(default)]
+    pub attachment: bool,
+    #[serde(default, deserialize_with = "status")]
+    pub status: Option<StatusCode>,
+    /// The smallest `Content-Length` to match, in bytes.
+    #[serde(default)]
+    pub min_content_length: Option<u64>,
+    /// The largest `Content-Length` to match, in bytes.
+    #[serde(default)]
+    pub max_content_length: Option<u64>,
+    pub directives: CacheControlDirectives,
+}
+
+impl RuleConfig {
+    /// Build the rule's matcher.
+    pub fn matcher(&self) -> Matcher {
+        let mut matcher = Matcher::any();
+
+        if let Some(prefix) = &self.path_prefix {
+            matcher = matcher.with_path_prefix(prefix);
+        }
+
+        if let Some(pattern) = &self.path_glob {
+            matcher = matcher.with_path_glob(pattern);
+        }
+
+        for method in self.methods.iter().flatten() {
+            matcher = matcher.with_method(method.clone());
+        }
+
+        for mime in &self.mime_types {
+            matcher = matcher.with_mime(mime.clone());
+        }
+
+        for family in &self.mime_families {
+            matcher = matcher.with_mime_family(family);
+        }
+
+        if self.fingerprinted {
+            matcher = matcher.with_fingerprinted();
+        }
+
+        if self.attachment {
+            matcher = matcher.with_attachment();
+        }
+
+        if let Some(status) = self.status {
+            matcher = matcher.with_status(status);
+        }
+
+        if self.min_content_length.is_some() || self.max_content_length.is_some() {
+            let min = self.min_content_length.unwrap_or(u64::MIN);
+            let max = self.max_content_length.unwrap_or(u64::MAX);
+            matcher = matcher.with_content_length(min..=max);
+        }
+
+        matcher
+    }
+}
+
+/// `Cache-Control` directives as written in a configuration file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct DirectivesConfig {
+    visibility: Option<Visibility>,
+    no_cache: bool,
+    no_store: bool,
+    #[serde(deserialize_with = "optional_duration")]
+    max_age: Option<Duration>,
+    #[serde(deserialize_with = "optional_duration")]
+    s_maxage: Option<Duration>,
+    must_revalidate: bool,
+    proxy_revalidate: bool,
+    no_transform: bool,
+    immutable: bool,
+    #[serde(deserialize_with = "optional_duration")]
+    stale_while_revalidate: Option<Duration>,
+    #[serde(deserialize_with = "optional_duration")]
+    stale_if_error: Option<Duration>,
+}
+
+impl From<DirectivesConfig> for CacheControlDirectives {
+    fn from(config: DirectivesConfig) -> Self {
+        let mut directives = CacheControlDirectives::new();
+
+        if let Some(visibility) = config.visibility {
+            directives = directives.with_visibility(visibility);
+        }
+
+        if config.no_cache {
+            directives = directives.no_cache();
+        }
+
+        if config.no_store {
+            directives = directives.no_store();
+        }
+
+        if let Some(max_age) = config.max_age {
+            directives = directives.with_max_age(max_age);
+        }
+
+        if let Some(s_maxage) = config.s_maxage {
+            directives = directives.with_s_maxage(s_maxage);
+        }
+
+        if config.must_revalidate {
+            directives = directives.must_revalidate();
+        }
+
+        if config.proxy_revalidate {
+            directives = directives.proxy_revalidate();
+        }
+
+        if config.no_transform {
+            directives = directives.no_transform();
+        }
+
+        if config.immutable {
+            directives = directives.immutable();
+        }
+
+        if let Some(duration) = config.stale_while_revalidate {
+            directives = directives.with_stale_while_revalidate(duration);
+        }
+
+        if let Some(duration) = config.stale_if_error {
+            directives = directives.with_stale_if_error(duration);
+        }
+
+        directives
+    }
+}
+
+impl<'de> Deserialize<'de> for CacheControlDirectives {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DirectivesConfig::deserialize(deserializer).map(Into::into)
+    }
+}
+
+impl<'de> Deserialize<'de> for MimeType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mime = String::deserialize(deserializer)?;
+        mime.parse()
+            .map_err(|_| de::Error::custom(format_args!("invalid MIME type `{mime}`")))
+    }
+}
+
+struct DurationVisitor;
+
+impl Visitor<'_> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a number of seconds or a duration like \"1h30m\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Duration, E> {
+        Ok(Duration::from_secs(secs))
+    }
+
+    fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Duration, E> {
+        u64::try_from(secs)
+            .map(Duration::from_secs)
+            .map_err(|_| E::custom("durations can't be negative"))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Duration, E> {
+        parse_duration(s).map_err(|_| E::custom(format_args!("invalid duration `{s}`")))
+    }
+}
+
+fn optional_duration<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+    d.deserialize_any(DurationVisitor).map(Some)
+}
+
+fn methods<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<Method>>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .into_iter()
+        .map(|method| {
+            Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .map_err(|_| de::Error::custom(format_args!("invalid method `{method}`")))
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+fn rules<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<RuleConfig>, D::Error> {
+    let rules = Vec::<RuleConfig>::deserialize(d)?;
+
+    // A matcher has a single path condition, so one would silently replace
+    // the other.
+    if rules
+        .iter()
+        .any(|rule| rule.path_prefix.is_some() && rule.path_glob.is_some())
+    {
+        return Err(de::Error::custom(
+            "a rule can't have both `path-prefix` and `path-glob`",
+        ));
+    }
+
+    Ok(rules)
+}
+
+fn header_names<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<HeaderName>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .into_iter()
+        .map(|name| {
+            HeaderName::try_from(name.as_str())
+                .map_err(|_| de::Error::custom(format_args!("invalid header name `{name}`")))
+        })
+        .collect()
+}
+
+fn status<'de, D: Deserializer<'de>>(d: D) -> Result<Option<StatusCode>, D::Error> {
+    let status = u16::deserialize(d)?;
+    StatusCode::from_u16(status)
+        .map(Some)
+        .map_err(|_| de::Error::custom(format_args!("invalid status code `{status}`")))
+}
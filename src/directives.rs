@@ -0,0 +1,317 @@
+use core::{fmt, time::Duration};
+
+#[cfg(feature = "std")]
+use http::HeaderValue;
+
+use crate::CacheControlError;
+
+/// The largest lifetime worth emitting. RFC 9111 has caches treat any larger
+/// number of seconds as this one, so a larger value is a mistake.
+const MAX_DELTA_SECONDS: u64 = 1 << 31;
+
+/// Whether a response may be stored by shared caches, or only by the browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Visibility {
+    /// `public`: any cache may store the response.
+    Public,
+    /// `private`: only the browser may store the response, which is what you
+    /// want for responses behind authentication.
+    Private,
+}
+
+/// A typed set of `Cache-Control` response directives.
+///
+/// Directives are serialized in a fixed order, regardless of the order the
+/// builder methods are called in:
+///
+/// ```rust
+/// use std::time::Duration;
+/// use axum_cc::CacheControlDirectives;
+///
+/// let directives = CacheControlDirectives::new()
+///     .with_max_age(Duration::from_secs(60))
+///     .public()
+///     .must_revalidate();
+///
+/// assert_eq!(directives.to_string(), "public, max-age=60, must-revalidate");
+/// ```
+///
+/// See
+/// <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cache-Control#response_directives>
+/// for what each directive means.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CacheControlDirectives {
+    visibility: Option<Visibility>,
+    no_cache: bool,
+    no_store: bool,
+    max_age: Option<Duration>,
+    s_maxage: Option<Duration>,
+    must_revalidate: bool,
+    proxy_revalidate: bool,
+    no_transform: bool,
+    immutable: bool,
+    stale_while_revalidate: Option<Duration>,
+    stale_if_error: Option<Duration>,
+}
+
+impl CacheControlDirectives {
+    /// Create an empty set of directives.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow shared caches to store the response. Replaces `private`.
+    pub fn public(self) -> Self {
+        self.with_visibility(Visibility::Public)
+    }
+
+    /// Only allow the browser to store the response. Replaces `public`.
+    pub fn private(self) -> Self {
+        self.with_visibility(Visibility::Private)
+    }
+
+    /// Set whether shared caches may store the response.
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Require caches to revalidate the response before every use.
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    /// Forbid caches from storing the response at all.
+    pub fn no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+
+    /// Set how long the response stays fresh.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Set how long the response stays fresh in shared caches, overriding
+    /// `max-age` for them.
+    pub fn with_s_maxage(mut self, s_maxage: Duration) -> Self {
+        self.s_maxage = Some(s_maxage);
+        self
+    }
+
+    /// Forbid caches from using the response once it is stale without
+    /// revalidating it first.
+    pub fn must_revalidate(mut self) -> Self {
+        self.must_revalidate = true;
+        self
+    }
+
+    /// Like `must-revalidate`, but only for shared caches.
+    pub fn proxy_revalidate(mut self) -> Self {
+        self.proxy_revalidate = true;
+        self
+    }
+
+    /// Forbid intermediaries from transforming the response, e.g. by
+    /// recompressing images.
+    pub fn no_transform(mut self) -> Self {
+        self.no_transform = true;
+        self
+    }
+
+    /// Indicate that the response will not change while it is fresh, so
+    /// browsers don't need to revalidate it on reload.
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// Allow caches to serve the response for this long after it becomes
+    /// stale, while they revalidate it in the background.
+    pub fn with_stale_while_revalidate(mut self, duration: Duration) -> Self {
+        self.stale_while_revalidate = Some(duration);
+        self
+    }
+
+    /// Allow caches to serve the response for this long after it becomes
+    /// stale if revalidating it fails (e.g. the origin returns a `5xx`).
+    pub fn with_stale_if_error(mut self, duration: Duration) -> Self {
+        self.stale_if_error = Some(duration);
+        self
+    }
+
+    /// The visibility (`public` or `private`), if set.
+    pub fn visibility(&self) -> Option<Visibility> {
+        self.visibility
+    }
+
+    /// The `max-age` value, if set.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// The `s-maxage` value, if set.
+    pub fn s_maxage(&self) -> Option<Duration> {
+        self.s_maxage
+    }
+
+    /// Returns `true` if no directives are set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Serialize the directives into a `Cache-Control` header value.
+    ///
+    /// Returns [`CacheControlError::EmptyDirectives`] if no directives are
+    /// set, since an empty `Cache-Control` header is almost certainly a
+    /// misconfiguration.
+    #[cfg(feature = "std")]
+    pub fn header_value(&self) -> Result<HeaderValue, CacheControlError> {
+        if self.is_empty() {
+            return Err(CacheControlError::EmptyDirectives);
+        }
+
+        // Every directive is a token or a number, so this can't fail.
+        Ok(HeaderValue::from_str(&self.to_string()).expect("directives are valid header values"))
+    }
+
+    /// Check that the directives make sense together, as RFC 9111 defines
+    /// them.
+    ///
+    /// Returns [`CacheControlError::EmptyDirectives`] if no directives are
+    /// set, [`CacheControlError::InvalidMaxAge`] if a `max-age` or `s-maxage`
+    /// is above 2<sup>31</sup> seconds (about 68 years), which caches treat as
+    /// that maximum anyway, and [`CacheControlError::ConflictingDirectives`]
+    /// if:
+    ///
+    /// - `no-store` is combined with `public`, a lifetime (`max-age`,
+    ///   `s-maxage`, `stale-while-revalidate`, or `stale-if-error`), or
+    ///   `immutable`, since nothing is stored to begin with.
+    /// - `immutable` is combined with `no-cache`, which asks caches to
+    ///   revalidate every time.
+    /// - `private` is combined with `s-maxage`, which only shared caches
+    ///   obey, and they may not store the response.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::{CacheControlDirectives, CacheControlError};
+    ///
+    /// let directives = CacheControlDirectives::new().no_cache().immutable();
+    /// assert_eq!(directives.validate(), Err(CacheControlError::ConflictingDirectives));
+    /// ```
+    pub fn validate(&self) -> Result<(), CacheControlError> {
+        if self.is_empty() {
+            return Err(CacheControlError::EmptyDirectives);
+        }
+
+        if [self.max_age, self.s_maxage]
+            .iter()
+            .flatten()
+            .any(|lifetime| lifetime.as_secs() > MAX_DELTA_SECONDS)
+        {
+            return Err(CacheControlError::InvalidMaxAge);
+        }
+
+        let stored = self.visibility == Some(Visibility::Public)
+            || self.max_age.is_some()
+            || self.s_maxage.is_some()
+            || self.stale_while_revalidate.is_some()
+            || self.stale_if_error.is_some()
+            || self.immutable;
+
+        if (self.no_store && stored)
+            || (self.immutable && self.no_cache)
+            || (self.visibility == Some(Visibility::Private) && self.s_maxage.is_some())
+        {
+            return Err(CacheControlError::ConflictingDirectives);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn set_max_age(&mut self, max_age: Option<Duration>) {
+        self.max_age = max_age;
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn set_s_maxage(&mut self, s_maxage: Option<Duration>) {
+        self.s_maxage = s_maxage;
+    }
+}
+
+impl fmt::Display for CacheControlDirectives {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut separator = "";
+        let mut write = |f: &mut fmt::Formatter<'_>, directive: fmt::Arguments<'_>| {
+            let result = write!(f, "{separator}{directive}");
+            separator = ", ";
+            result
+        };
+
+        match self.visibility {
+            Some(Visibility::Public) => write(f, format_args!("public"))?,
+            Some(Visibility::Private) => write(f, format_args!("private"))?,
+            None => {}
+        }
+
+        if self.no_cache {
+            write(f, format_args!("no-cache"))?;
+        }
+
+        if self.no_store {
+            write(f, format_args!("no-store"))?;
+        }
+
+        if let Some(max_age) = self.max_age {
+            write(f, format_args!("max-age={}", max_age.as_secs()))?;
+        }
+
+        if let Some(s_maxage) = self.s_maxage {
+            write(f, format_args!("s-maxage={}", s_maxage.as_secs()))?;
+        }
+
+        if self.must_revalidate {
+            write(f, format_args!("must-revalidate"))?;
+        }
+
+        if self.proxy_revalidate {
+            write(f, format_args!("proxy-revalidate"))?;
+        }
+
+        if self.no_transform {
+            write(f, format_args!("no-transform"))?;
+        }
+
+        if self.immutable {
+            write(f, format_args!("immutable"))?;
+        }
+
+        if let Some(duration) = self.stale_while_revalidate {
+            write(
+                f,
+                format_args!("stale-while-revalidate={}", duration.as_secs()),
+            )?;
+        }
+
+        if let Some(duration) = self.stale_if_error {
+            write(f, format_args!("stale-if-error={}", duration.as_secs()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Duration> for CacheControlDirectives {
+    /// Create `public, max-age=N` directives.
+    fn from(max_age: Duration) -> Self {
+        Self::new().public().with_max_age(max_age)
+    }
+}
@@ -0,0 +1,69 @@
+use core::time::Duration;
+
+use crate::error::CacheControlError;
+
+/// Parse a human-friendly duration, such as `"30d"` or `"1h30m"`.
+///
+/// A duration is a number of seconds on its own (`"90"`), or a sequence of
+/// numbers with units: `s` for seconds, `m` for minutes, `h` for hours, `d`
+/// for days, `w` for weeks, and `y` for years of 365 days. Whitespace between
+/// the parts is allowed.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum_cc::{parse_duration, CacheControlLayer};
+///
+/// assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+/// assert!(parse_duration("soon").is_err());
+///
+/// let layer = CacheControlLayer::new().with_max_age(parse_duration("30d")?);
+/// # Ok::<(), axum_cc::CacheControlError>(())
+/// ```
+///
+/// Returns [`CacheControlError::InvalidDuration`] for anything else, or if
+/// the duration overflows.
+pub fn parse_duration(s: &str) -> Result<Duration, CacheControlError> {
+    let s = s.trim();
+
+    if let Ok(secs) = s.parse() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    if s.is_empty() {
+        return Err(CacheControlError::InvalidDuration);
+    }
+
+    let mut total: u64 = 0;
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let (number, tail) = rest.split_at(digits);
+        let number: u64 = number
+            .parse()
+            .map_err(|_| CacheControlError::InvalidDuration)?;
+
+        let tail = tail.trim_start();
+        let unit = tail.bytes().take_while(u8::is_ascii_alphabetic).count();
+        let (unit, tail) = tail.split_at(unit);
+
+        let secs = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 60 * 60 * 24,
+            "w" => 60 * 60 * 24 * 7,
+            "y" => 60 * 60 * 24 * 365,
+            _ => return Err(CacheControlError::InvalidDuration),
+        };
+
+        total = number
+            .checked_mul(secs)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or(CacheControlError::InvalidDuration)?;
+        rest = tail.trim_start();
+    }
+
+    Ok(Duration::from_secs(total))
+}
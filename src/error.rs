@@ -0,0 +1,36 @@
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheControlError {
+    InvalidMaxAge,
+    InvalidMimeType,
+    /// A duration string that [`parse_duration`](crate::parse_duration)
+    /// can't read.
+    InvalidDuration,
+    /// Directives that would serialize to an empty `Cache-Control` header.
+    EmptyDirectives,
+    /// Directives that contradict each other, such as `no-store` with a
+    /// `max-age`.
+    ConflictingDirectives,
+    /// A layer without MIME types, policies, rules, or functions, which
+    /// never matches a response.
+    NoPolicies,
+}
+
+impl fmt::Display for CacheControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheControlError::InvalidMaxAge => write!(f, "invalid max-age value"),
+            CacheControlError::InvalidMimeType => write!(f, "invalid MIME type"),
+            CacheControlError::InvalidDuration => write!(f, "invalid duration"),
+            CacheControlError::EmptyDirectives => write!(f, "no Cache-Control directives set"),
+            CacheControlError::ConflictingDirectives => {
+                write!(f, "conflicting Cache-Control directives")
+            }
+            CacheControlError::NoPolicies => write!(f, "the layer matches no responses"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CacheControlError {}
@@ -0,0 +1,392 @@
+//! `ETag` generation, so clients can revalidate responses instead of
+//! downloading them again.
+//!
+//! [`EtagLayer`] buffers the bodies of successful `GET` responses, hashes
+//! them, and sets a strong `ETag`. Requests whose `If-None-Match` matches
+//! the response's `ETag`, whether generated or set by the handler, get an
+//! empty `304 Not Modified` instead.
+//!
+//! A `304` keeps the response's caching headers but drops its
+//! `Content-Type`, so the `EtagLayer` should wrap any [`CacheControlLayer`]
+//! rather than the other way around:
+//!
+//! ```rust
+//! use axum::{routing::get, Router};
+//! use axum_cc::{etag::EtagLayer, CacheControlLayer, MimeType};
+//!
+//! // Layers added later wrap those added before them.
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "hello" }))
+//!     .layer(CacheControlLayer::new())
+//!     .layer(EtagLayer::new().with_mime_types(&[MimeType::HTML, MimeType::JSON]));
+//! ```
+//!
+//! [`CacheControlLayer`]: crate::CacheControlLayer
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::response::Response;
+use futures_core::ready;
+use http::{
+    header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    response, HeaderMap, HeaderValue, Method, Request, StatusCode,
+};
+use http_body::Body;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    body::{not_modified, Buffer, BufferedBody, Forwarded},
+    cache_status,
+    mime::MimeType,
+};
+
+/// The largest body hashed by default.
+const DEFAULT_MAX_SIZE: usize = 1024 * 1024;
+
+/// Whether generated entity tags are strong or weak.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EtagStrength {
+    /// Tags like `"5-a430d84680aabd0b"`, promising the body is identical
+    /// byte for byte.
+    #[default]
+    Strong,
+    /// Tags like `W/"5-a430d84680aabd0b"`, only promising the body is
+    /// semantically equivalent. Use these if the response is transformed
+    /// further out, for example by compression middleware, since caches may
+    /// not combine ranges of weakly tagged responses.
+    Weak,
+}
+
+/// A layer that sets an `ETag` on responses, computed from a hash of their
+/// body, and answers matching `If-None-Match` requests with `304 Not
+/// Modified`.
+///
+/// Only `200 OK` responses to `GET` requests without an `ETag` of their own
+/// are tagged; `HEAD` responses have no body to hash. The body is buffered
+/// while it is hashed, so bodies larger than the maximum size are passed on
+/// untagged as soon as they exceed it.
+///
+/// `If-None-Match` is checked on `200 OK` responses to `GET` and `HEAD`
+/// requests, comparing tags as the layer's [`EtagStrength`] says: strongly by
+/// default, so that only identical strong tags match, or weakly, so that
+/// `W/"a"` and `"a"` match each other too.
+#[derive(Debug, Clone)]
+pub struct EtagLayer {
+    mime_types: Option<Arc<[MimeType]>>,
+    max_size: usize,
+    strength: EtagStrength,
+    cache_status: bool,
+}
+
+impl Default for EtagLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EtagLayer {
+    /// Create a new `EtagLayer` that tags responses of any MIME type with
+    /// bodies up to 1 MiB.
+    pub fn new() -> Self {
+        Self {
+            mime_types: None,
+            max_size: DEFAULT_MAX_SIZE,
+            strength: EtagStrength::Strong,
+            cache_status: false,
+        }
+    }
+
+    /// Set whether generated tags are strong or weak, and whether
+    /// `If-None-Match` is compared strongly or weakly.
+    ///
+    /// Weak comparison is what RFC 9110 specifies for `If-None-Match`, and
+    /// also answers requests for weak tags set by handlers or added further
+    /// out with a `304`.
+    ///
+    /// ```rust
+    /// use axum_cc::etag::{EtagLayer, EtagStrength};
+    ///
+    /// let layer = EtagLayer::new().with_strength(EtagStrength::Weak);
+    /// ```
+    pub fn with_strength(mut self, strength: EtagStrength) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Only tag responses with one of the given MIME types.
+    pub fn with_mime_types(mut self, mime_types: &[MimeType]) -> Self {
+        self.mime_types = Some(mime_types.into());
+        self
+    }
+
+    /// Set the size of the largest body to hash, in bytes.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Report what the layer did in a `Cache-Status` header (RFC 9211).
+    ///
+    /// The layer adds an `axum-cc` entry with the status the inner service
+    /// responded with, and `detail=etag-match` if it replaced the response
+    /// with a `304`:
+    ///
+    /// ```text
+    /// Cache-Status: axum-cc; fwd=uri-miss; fwd-status=200; detail=etag-match
+    /// ```
+    pub fn with_cache_status(mut self) -> Self {
+        self.cache_status = true;
+        self
+    }
+
+    /// Returns `true` if a response should be tagged.
+    fn applies(&self, response: &response::Parts) -> bool {
+        if response.headers.contains_key(ETAG) {
+            return false;
+        }
+
+        let Some(mime_types) = &self.mime_types else {
+            return true;
+        };
+
+        response
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| MimeType::try_from(value).ok())
+            .is_some_and(|mime| mime_types.contains(&mime))
+    }
+}
+
+impl<S> Layer<S> for EtagLayer {
+    type Service = Etag<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Etag {
+            inner,
+            layer: Arc::new(self.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Etag<S> {
+    inner: S,
+    layer: Arc<EtagLayer>,
+}
+
+impl<S, T, B> Service<Request<T>> for Etag<S>
+where
+    S: Service<Request<T>, Response = Response<B>>,
+    B: Body,
+{
+    type Response = Response<BufferedBody<B>>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        let get = req.method() == Method::GET;
+        let conditional = get || req.method() == Method::HEAD;
+
+        let if_none_match = if conditional {
+            req.headers()
+                .get_all(IF_NONE_MATCH)
+                .iter()
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        ResponseFuture {
+            response_future: self.inner.call(req),
+            layer: self.layer.clone(),
+            get,
+            conditional,
+            if_none_match,
+            buffering: None,
+        }
+    }
+}
+
+pin_project! {
+    pub struct ResponseFuture<F, B>
+    where
+        B: Body,
+    {
+        #[pin]
+        response_future: F,
+        layer: Arc<EtagLayer>,
+        get: bool,
+        conditional: bool,
+        if_none_match: Vec<HeaderValue>,
+        // The response, held back while its body is buffered.
+        buffering: Option<(response::Parts, Buffer<B>)>,
+    }
+}
+
+impl<F, B, E> ResponseFuture<F, B>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body,
+{
+    /// Poll for the response, along with the status the inner service
+    /// responded with.
+    fn poll_response(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Forwarded<B>, E>> {
+        let this = self.project();
+
+        if this.buffering.is_none() {
+            let response = ready!(this.response_future.poll(cx))?;
+            let (parts, body) = response.into_parts();
+            let status = parts.status;
+
+            if status != StatusCode::OK {
+                let response = Response::from_parts(parts, BufferedBody::new(body));
+                return Poll::Ready(Ok((response, status)));
+            }
+
+            if let Some(etag) = parts.headers.get(ETAG) {
+                if none_match(this.if_none_match, etag, this.layer.strength) {
+                    let response = not_modified(parts, Buffer::new(body));
+                    return Poll::Ready(Ok((response, status)));
+                }
+            }
+
+            if !*this.get || !this.layer.applies(&parts) {
+                let response = Response::from_parts(parts, BufferedBody::new(body));
+                return Poll::Ready(Ok((response, status)));
+            }
+
+            *this.buffering = Some((parts, Buffer::new(body)));
+        }
+
+        let Some((_, buffer)) = this.buffering else {
+            unreachable!("the response is buffered above");
+        };
+
+        // Reading one byte past the maximum tells us the body is too large.
+        ready!(buffer.poll_fill(cx, this.layer.max_size.saturating_add(1)));
+
+        let Some((mut parts, buffer)) = this.buffering.take() else {
+            unreachable!("the response is buffered above");
+        };
+
+        if buffer.is_complete() {
+            let tag = etag(buffer.data(), this.layer.strength);
+
+            if let Ok(value) = HeaderValue::from_str(&tag) {
+                let matched = none_match(this.if_none_match, &value, this.layer.strength);
+                parts.headers.insert(ETAG, value);
+
+                if matched {
+                    return Poll::Ready(Ok((not_modified(parts, buffer), StatusCode::OK)));
+                }
+            }
+        }
+
+        let response = Response::from_parts(parts, buffer.into_body());
+        Poll::Ready(Ok((response, StatusCode::OK)))
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F, B>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body,
+{
+    type Output = Result<Response<BufferedBody<B>>, E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (mut response, status) = ready!(self.as_mut().poll_response(cx))?;
+
+        if self.layer.cache_status {
+            let detail = (response.status() != status).then_some("etag-match");
+            cache_status::append(response.headers_mut(), self.conditional, status, detail);
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
+
+/// Returns `true` if any of the `If-None-Match` header values matches
+/// `etag`, by strong comparison if `strength` is [`EtagStrength::Strong`] and
+/// by weak comparison otherwise.
+fn none_match(if_none_match: &[HeaderValue], etag: &HeaderValue, strength: EtagStrength) -> bool {
+    let Some((weak, etag)) = entity_tags(etag.as_bytes()).next() else {
+        return false;
+    };
+
+    // Strong comparison never matches weak tags, on either side.
+    let strong = strength == EtagStrength::Strong;
+
+    if strong && weak {
+        return false;
+    }
+
+    if_none_match.iter().any(|value| {
+        value.as_bytes().trim_ascii() == b"*"
+            || entity_tags(value.as_bytes()).any(|(weak, tag)| !(strong && weak) && tag == etag)
+    })
+}
+
+/// Returns the `ETag` in `headers` with a `W/` prefix, if it's a strong tag.
+pub(crate) fn weaken(headers: &HeaderMap) -> Option<HeaderValue> {
+    let tag = headers.get(ETAG)?.as_bytes().trim_ascii();
+
+    if !tag.starts_with(b"\"") {
+        return None;
+    }
+
+    HeaderValue::from_bytes(&[b"W/", tag].concat()).ok()
+}
+
+/// Split a list of entity tags into whether they're weak and their opaque
+/// parts, quotes included. Stops at the first malformed tag.
+fn entity_tags(mut list: &[u8]) -> impl Iterator<Item = (bool, &[u8])> {
+    std::iter::from_fn(move || {
+        list = list.trim_ascii_start();
+
+        while let Some(rest) = list.strip_prefix(b",") {
+            list = rest.trim_ascii_start();
+        }
+
+        let weak = list.strip_prefix(b"W/");
+        let tag = weak.unwrap_or(list);
+        let end = tag.strip_prefix(b"\"")?.iter().position(|&b| b == b'"')? + 2;
+        let (opaque, rest) = tag.split_at(end);
+
+        list = rest;
+        Some((weak.is_some(), opaque))
+    })
+}
+
+/// Compute an entity tag for a body from its length and FNV-1a hash, which,
+/// unlike `DefaultHasher`, is stable across builds and deployments.
+fn etag<'a>(chunks: impl Iterator<Item = &'a bytes::Bytes>, strength: EtagStrength) -> String {
+    let mut len = 0;
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for chunk in chunks {
+        len += chunk.len();
+
+        for &byte in chunk.iter() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    match strength {
+        EtagStrength::Strong => format!("\"{len:x}-{hash:016x}\""),
+        EtagStrength::Weak => format!("W/\"{len:x}-{hash:016x}\""),
+    }
+}
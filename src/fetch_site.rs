@@ -0,0 +1,53 @@
+use core::fmt::Display;
+
+#[cfg(feature = "std")]
+use http::{HeaderName, HeaderValue};
+
+/// The `Sec-Fetch-Site` request header.
+#[cfg(feature = "std")]
+pub const SEC_FETCH_SITE: HeaderName = HeaderName::from_static("sec-fetch-site");
+
+/// The relationship between the origin that initiated a request and the
+/// origin of the requested resource, as reported by the `Sec-Fetch-Site`
+/// request header.
+///
+/// See
+/// <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Sec-Fetch-Site>
+/// for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FetchSite {
+    CrossSite,
+    SameOrigin,
+    SameSite,
+    None,
+}
+
+impl FetchSite {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FetchSite::CrossSite => "cross-site",
+            FetchSite::SameOrigin => "same-origin",
+            FetchSite::SameSite => "same-site",
+            FetchSite::None => "none",
+        }
+    }
+
+    /// Parse a `Sec-Fetch-Site` header value, returning `None` for values
+    /// that are not defined by the specification.
+    #[cfg(feature = "std")]
+    pub fn from_header(header: &HeaderValue) -> Option<Self> {
+        match header.as_bytes() {
+            b"cross-site" => Some(FetchSite::CrossSite),
+            b"same-origin" => Some(FetchSite::SameOrigin),
+            b"same-site" => Some(FetchSite::SameSite),
+            b"none" => Some(FetchSite::None),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FetchSite {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
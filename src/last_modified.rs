@@ -0,0 +1,254 @@
+//! `Last-Modified` headers and `If-Modified-Since` requests.
+//!
+//! [`LastModifiedLayer`] sets `Last-Modified` from a [`LastModified`]
+//! response extension or a custom source, and answers `If-Modified-Since`
+//! requests for unchanged responses with an empty `304 Not Modified`.
+//! Responses that already carry `Last-Modified`, such as files served by
+//! `tower_http::services::ServeDir`, are answered from that header.
+//!
+//! Like [`EtagLayer`], it should wrap any [`CacheControlLayer`]:
+//!
+//! ```rust
+//! use std::time::SystemTime;
+//!
+//! use axum::{
+//!     response::{IntoResponse, Response},
+//!     routing::get,
+//!     Router,
+//! };
+//! use axum_cc::{last_modified::{LastModified, LastModifiedLayer}, CacheControlLayer};
+//!
+//! async fn handler() -> Response {
+//!     let mut response = "hello".into_response();
+//!     response
+//!         .extensions_mut()
+//!         .insert(LastModified(SystemTime::UNIX_EPOCH));
+//!     response
+//! }
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(handler))
+//!     .layer(CacheControlLayer::new())
+//!     .layer(LastModifiedLayer::new());
+//! ```
+//!
+//! [`EtagLayer`]: crate::etag::EtagLayer
+//! [`CacheControlLayer`]: crate::CacheControlLayer
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::SystemTime,
+};
+
+use axum_core::response::Response;
+use futures_core::ready;
+use http::{
+    header::{IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    response, HeaderValue, Method, Request, StatusCode,
+};
+use http_body::Body;
+use httpdate::HttpDate;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    body::{not_modified, Buffer, BufferedBody},
+    cache_status,
+};
+
+/// When a response was last modified, inserted into the response's
+/// extensions by a handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LastModified(pub SystemTime);
+
+type SourceFnInner = dyn Fn(&response::Parts) -> Option<SystemTime> + Send + Sync;
+
+#[derive(Clone)]
+struct SourceFn(Arc<SourceFnInner>);
+
+impl fmt::Debug for SourceFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SourceFn")
+    }
+}
+
+/// A layer that sets `Last-Modified` on responses and answers
+/// `If-Modified-Since` requests with `304 Not Modified`.
+///
+/// Only `200 OK` responses to `GET` and `HEAD` requests are considered. As
+/// RFC 9110 requires, `If-Modified-Since` is ignored on requests that also
+/// carry `If-None-Match`.
+#[derive(Debug, Clone, Default)]
+pub struct LastModifiedLayer {
+    source: Option<SourceFn>,
+    cache_status: bool,
+}
+
+impl LastModifiedLayer {
+    /// Create a new `LastModifiedLayer` that takes modification times from
+    /// [`LastModified`] response extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take modification times from a function instead of [`LastModified`]
+    /// extensions.
+    ///
+    /// The function is only called for responses without a `Last-Modified`
+    /// header.
+    ///
+    /// ```rust
+    /// use std::time::SystemTime;
+    ///
+    /// use axum_cc::last_modified::LastModifiedLayer;
+    ///
+    /// #[derive(Clone)]
+    /// struct Revision(SystemTime);
+    ///
+    /// let layer = LastModifiedLayer::new()
+    ///     .with_source(|response| response.extensions.get::<Revision>().map(|r| r.0));
+    /// ```
+    pub fn with_source<F>(mut self, source: F) -> Self
+    where
+        F: Fn(&response::Parts) -> Option<SystemTime> + Send + Sync + 'static,
+    {
+        self.source = Some(SourceFn(Arc::new(source)));
+        self
+    }
+
+    /// Report what the layer did in a `Cache-Status` header (RFC 9211).
+    ///
+    /// The layer adds an `axum-cc` entry with the status the inner service
+    /// responded with, and `detail=not-modified-since` if it replaced the
+    /// response with a `304`:
+    ///
+    /// ```text
+    /// Cache-Status: axum-cc; fwd=uri-miss; fwd-status=200; detail=not-modified-since
+    /// ```
+    pub fn with_cache_status(mut self) -> Self {
+        self.cache_status = true;
+        self
+    }
+
+    /// Find when a response was last modified, setting `Last-Modified` if
+    /// it's missing.
+    fn last_modified(&self, response: &mut response::Parts) -> Option<HttpDate> {
+        if let Some(value) = response.headers.get(LAST_MODIFIED) {
+            return value.to_str().ok()?.parse().ok();
+        }
+
+        let time = match &self.source {
+            Some(SourceFn(source)) => source(response)?,
+            None => response.extensions.get::<LastModified>()?.0,
+        };
+
+        let date = HttpDate::from(time);
+
+        if let Ok(value) = HeaderValue::from_str(&date.to_string()) {
+            response.headers.insert(LAST_MODIFIED, value);
+        }
+
+        Some(date)
+    }
+}
+
+impl<S> Layer<S> for LastModifiedLayer {
+    type Service = LastModifiedService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LastModifiedService {
+            inner,
+            layer: Arc::new(self.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LastModifiedService<S> {
+    inner: S,
+    layer: Arc<LastModifiedLayer>,
+}
+
+impl<S, T, B> Service<Request<T>> for LastModifiedService<S>
+where
+    S: Service<Request<T>, Response = Response<B>>,
+    B: Body,
+{
+    type Response = Response<BufferedBody<B>>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        let conditional = [Method::GET, Method::HEAD].contains(req.method());
+        let headers = req.headers();
+
+        let if_modified_since = if conditional && !headers.contains_key(IF_NONE_MATCH) {
+            headers
+                .get(IF_MODIFIED_SINCE)
+                .and_then(|value| value.to_str().ok()?.parse().ok())
+        } else {
+            None
+        };
+
+        ResponseFuture {
+            response_future: self.inner.call(req),
+            layer: self.layer.clone(),
+            conditional,
+            if_modified_since,
+        }
+    }
+}
+
+pin_project! {
+    pub struct ResponseFuture<F> {
+        #[pin]
+        response_future: F,
+        layer: Arc<LastModifiedLayer>,
+        conditional: bool,
+        if_modified_since: Option<HttpDate>,
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body,
+{
+    type Output = Result<Response<BufferedBody<B>>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = ready!(this.response_future.poll(cx))?;
+        let (mut parts, body) = response.into_parts();
+        let status = parts.status;
+
+        let mut response = if !*this.conditional || status != StatusCode::OK {
+            Response::from_parts(parts, BufferedBody::new(body))
+        } else {
+            let last_modified = this.layer.last_modified(&mut parts);
+
+            match (last_modified, this.if_modified_since) {
+                (Some(last_modified), Some(since)) if last_modified <= *since => {
+                    not_modified(parts, Buffer::new(body))
+                }
+                _ => Response::from_parts(parts, BufferedBody::new(body)),
+            }
+        };
+
+        if this.layer.cache_status {
+            let detail = (response.status() != status).then_some("not-modified-since");
+            cache_status::append(response.headers_mut(), *this.conditional, status, detail);
+        }
+
+        Poll::Ready(Ok(response))
+    }
+}
@@ -0,0 +1,2771 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    future::Future,
+    hash::{BuildHasher, Hasher, RandomState},
+    num::NonZeroU64,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use axum_core::response::Response;
+use futures_core::ready;
+#[cfg(feature = "conditional")]
+use http::header::ETAG;
+use http::{
+    header::{
+        ACCEPT, ACCEPT_ENCODING, AGE, AUTHORIZATION, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_TYPE,
+        EXPIRES, LAST_MODIFIED, PRAGMA, SET_COOKIE,
+    },
+    request, response, HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode,
+};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+#[cfg(feature = "serde")]
+use crate::config::CacheControlConfig;
+#[cfg(feature = "conditional")]
+use crate::etag::{self, EtagStrength};
+use crate::{
+    directives::{CacheControlDirectives, Visibility},
+    error::CacheControlError,
+    fetch_site::{FetchSite, SEC_FETCH_SITE},
+    merge,
+    mime::{MimeSet, MimeType},
+    outcome::{Outcome, SkipReason},
+    path,
+    policy::CachePolicy,
+    resolver::{CachePolicyResolver, ResolveFuture},
+    response::{DryRun, StoredAt},
+    rule::{KeyFn, MatchContext, Matcher, Rule},
+    tags::CacheTagHeader,
+    targeted::TargetedField,
+    vary,
+};
+
+const DEFAULT_MIME_TYPES: [MimeType; 6] = [
+    MimeType::CSS,
+    MimeType::JS,
+    MimeType::SVG,
+    MimeType::WEBP,
+    MimeType::WOFF2,
+    MimeType::PNG,
+];
+
+/// The MIME types of the static assets a web app typically ships, used by the
+/// asset presets.
+const ASSET_MIME_TYPES: [MimeType; 14] = [
+    MimeType::CSS,
+    MimeType::JS,
+    MimeType::WASM,
+    MimeType::SVG,
+    MimeType::WEBP,
+    MimeType::AVIF,
+    MimeType::PNG,
+    MimeType::JPEG,
+    MimeType::GIF,
+    MimeType::ICO,
+    MimeType::WOFF2,
+    MimeType::TTF,
+    MimeType::OTF,
+    MimeType::EOT,
+];
+
+/// The methods a layer applies to by default. Responses to unsafe methods
+/// like `POST` aren't reusable, so there's no point in caching them.
+static SAFE_METHODS: [Method; 2] = [Method::GET, Method::HEAD];
+
+static CACHE_CONTROL_RULE: HeaderName = HeaderName::from_static("x-cache-control-rule");
+
+/// How the layer treats responses to range requests.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum RangePolicy {
+    /// Set `Cache-Control` on `206 Partial Content` responses just like on
+    /// full responses.
+    #[default]
+    Include,
+    /// Only set `Cache-Control` on full responses, leaving `206 Partial
+    /// Content` responses untouched.
+    SkipPartial,
+}
+
+/// What the layer does with a `Cache-Control` header that is already present
+/// on the response.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum OverwriteBehavior {
+    /// Replace the existing header with the layer's.
+    Always,
+    /// Leave the response untouched, so handlers can opt out of the layer's
+    /// policy by setting their own header.
+    ///
+    /// Headers set along with a [`CachePolicy`], as by [`Cached`] and
+    /// [`NoCache`], are still downgraded by the layer's guards.
+    ///
+    /// [`Cached`]: crate::Cached
+    /// [`NoCache`]: crate::NoCache
+    #[default]
+    SkipIfPresent,
+    /// Merge the layer's directives into the existing header.
+    ///
+    /// When both set the same directive, the layer's value wins. Restrictions
+    /// set by the existing header are always kept, though: `private` and
+    /// `no-cache` survive the merge, and a response marked `no-store` is left
+    /// as is. Directives that would contradict a restriction are dropped, so
+    /// `private` removes `public` and `s-maxage`, `no-cache` removes
+    /// `immutable`, and the layer's `no-store` removes everything that lets
+    /// caches store the response.
+    ///
+    /// ```text
+    /// existing: private, max-age=60
+    /// layer:    public, max-age=3600
+    /// merged:   private, max-age=3600
+    /// ```
+    ///
+    /// Headers that aren't valid UTF-8 can't be merged and are left alone.
+    Merge,
+}
+
+/// A point in time at which responses expire, however long ago they were
+/// fetched.
+///
+/// See [`CacheControlLayer::with_expiry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiry {
+    /// Expire every day at this time after midnight UTC, for content that is
+    /// refreshed on a daily schedule.
+    DailyAt(Duration),
+    /// Expire at a fixed point in time.
+    At(SystemTime),
+    /// Expire this long after the response's `Last-Modified` date. Responses
+    /// without a (valid) `Last-Modified` header keep their policy's
+    /// `max-age`.
+    AfterModified(Duration),
+}
+
+impl Expiry {
+    /// The time left until the deadline, from `now`, or `None` if there is
+    /// no deadline for a response with these headers.
+    fn remaining(&self, headers: &HeaderMap, now: SystemTime) -> Option<Duration> {
+        const DAY: u64 = 60 * 60 * 24;
+
+        let deadline = match *self {
+            Expiry::DailyAt(offset) => {
+                let since_epoch = now.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+                let today = since_epoch.as_secs() % DAY;
+                let offset = offset.as_secs() % DAY;
+                let until = (offset + DAY - today - 1) % DAY + 1;
+
+                return Some(Duration::from_secs(until));
+            }
+            Expiry::At(deadline) => deadline,
+            Expiry::AfterModified(ttl) => {
+                let last_modified = headers.get(LAST_MODIFIED)?.to_str().ok()?;
+                httpdate::parse_http_date(last_modified)
+                    .ok()?
+                    .checked_add(ttl)?
+            }
+        };
+
+        Some(deadline.duration_since(now).unwrap_or_default())
+    }
+}
+
+/// How the layer treats responses that may contain per-user content, such as
+/// responses that set cookies, or that shouldn't be cached for long, such as
+/// errors.
+///
+/// When several downgrades apply to the same response, the one listed last
+/// here wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Downgrade {
+    /// Apply the layer's policy as usual.
+    Ignore,
+    /// Apply the layer's policy, but mark it `private` so that only the
+    /// user's browser may store the response.
+    Private,
+    /// Emit `no-store` instead of the layer's policy.
+    NoStore,
+    /// Leave the response untouched.
+    Skip,
+}
+
+/// A user-provided function that picks the directives for a response.
+type PolicyFnInner =
+    dyn Fn(&request::Parts, &response::Parts) -> Option<CacheControlDirectives> + Send + Sync;
+
+#[derive(Clone)]
+struct PolicyFn(Arc<PolicyFnInner>);
+
+impl fmt::Debug for PolicyFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PolicyFn")
+    }
+}
+
+/// A user-provided function told what the layer did with each response.
+type ObserverInner = dyn Fn(&Outcome<'_>) + Send + Sync;
+
+#[derive(Clone)]
+struct Observer(Arc<ObserverInner>);
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Observer")
+    }
+}
+
+#[derive(Clone)]
+struct Resolver(Arc<dyn CachePolicyResolver>);
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Resolver")
+    }
+}
+
+/// `Cache-Control` values computed once the layer is built, for policies
+/// whose value doesn't depend on the response. The vectors are parallel to
+/// the layer's policies and rules.
+#[derive(Debug, Clone, Default)]
+struct Precomputed {
+    mime_types: MimeSet,
+    directives: Option<HeaderValue>,
+    fingerprinted: Option<HeaderValue>,
+    default_policy: Option<HeaderValue>,
+    permanent_redirect: Option<HeaderValue>,
+    temporary_redirect: Option<HeaderValue>,
+    policies: Vec<Option<HeaderValue>>,
+    rules: Vec<Option<HeaderValue>>,
+}
+
+/// A [`tower::Layer`] that sets `Cache-Control` headers on responses.
+///
+/// See
+/// <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cache-Control>
+/// for more information.
+#[derive(Debug, Clone)]
+pub struct CacheControlLayer {
+    mime_types: Arc<[MimeType]>,
+    directives: CacheControlDirectives,
+    policies: Vec<(MimeType, CacheControlDirectives)>,
+    rules: Vec<Rule>,
+    policy_fn: Option<PolicyFn>,
+    resolver: Option<Resolver>,
+    observer: Option<Observer>,
+    fetch_site_rules: Vec<(FetchSite, HeaderValue)>,
+    marker_header: Option<String>,
+    skip_if_headers: Vec<HeaderName>,
+    methods: Option<Vec<Method>>,
+    dry_run: bool,
+    rule_header: bool,
+    age_decay: bool,
+    age: bool,
+    content_negotiation_vary: bool,
+    vary: Vec<HeaderName>,
+    policy_vary: Vec<(MimeType, Vec<HeaderName>)>,
+    mime_from_path: bool,
+    expires: bool,
+    pragma: bool,
+    #[cfg(feature = "conditional")]
+    etag_strength: EtagStrength,
+    mtime_buckets: Vec<(Duration, Duration)>,
+    max_age_jitter: Option<Duration>,
+    expiry: Option<Expiry>,
+    /// The directives for URLs that change whenever their content does, set
+    /// once fingerprinting or version parameters are enabled.
+    fingerprinted: Option<CacheControlDirectives>,
+    fingerprint_paths: bool,
+    version_params: Vec<String>,
+    default_policy: Option<CacheControlDirectives>,
+    permanent_redirect: Option<CacheControlDirectives>,
+    temporary_redirect: Option<CacheControlDirectives>,
+    range_policy: RangePolicy,
+    overwrite: OverwriteBehavior,
+    set_cookie: Downgrade,
+    authorization: Downgrade,
+    error: Downgrade,
+    attachment: Downgrade,
+    encoding_rules: Vec<(String, Duration)>,
+    smart_vary_encoding: bool,
+    encoding_vary: bool,
+    max_age_ceiling: Option<Duration>,
+    cache_tags: Option<(HeaderName, HeaderValue)>,
+    targeted: Vec<(HeaderName, HeaderValue)>,
+    precomputed: Precomputed,
+}
+
+impl Default for CacheControlLayer {
+    /// Create a `CacheControlLayer` that matches no MIME types and emits
+    /// `public, max-age=0`.
+    fn default() -> Self {
+        Self {
+            mime_types: Arc::new([]),
+            directives: CacheControlDirectives::new()
+                .public()
+                .with_max_age(Duration::ZERO),
+            ..Self::new()
+        }
+    }
+}
+
+impl CacheControlLayer {
+    /// Create a new `CacheControlLayer` with the default configuration.
+    ///
+    /// The default configuration sets `Cache-Control` headers for the following
+    /// MIME types:
+    ///
+    /// - `text/css`
+    /// - `application/javascript`
+    /// - `image/svg+xml`
+    /// - `image/webp`
+    /// - `font/woff2`
+    ///
+    /// The `max-age` value is set to 1 year.
+    ///
+    /// If you wish to set your own MIME types and/or `max-age` value, use
+    /// [`CacheControlLayer::with_mime_types`] and/or
+    /// [`CacheControlLayer::with_max_age`].
+    ///
+    /// As these are builder methods, you can chain them together:
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::{CacheControlLayer, MimeType};
+    ///
+    /// let layer = CacheControlLayer::default() // empty headers
+    ///    .with_mime_types(&[MimeType::CSS])
+    ///    .with_max_age(Duration::from_secs(86400));
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            mime_types: Arc::new(DEFAULT_MIME_TYPES),
+            directives: CacheControlDirectives::new()
+                .public()
+                .with_max_age(Duration::from_secs(60 * 60 * 24 * 365)),
+            policies: Vec::new(),
+            rules: Vec::new(),
+            policy_fn: None,
+            resolver: None,
+            observer: None,
+            fetch_site_rules: Vec::new(),
+            marker_header: None,
+            skip_if_headers: Vec::new(),
+            methods: Some(SAFE_METHODS.to_vec()),
+            dry_run: false,
+            rule_header: false,
+            age_decay: false,
+            age: false,
+            content_negotiation_vary: false,
+            vary: Vec::new(),
+            policy_vary: Vec::new(),
+            mime_from_path: false,
+            expires: false,
+            pragma: false,
+            #[cfg(feature = "conditional")]
+            etag_strength: EtagStrength::Strong,
+            mtime_buckets: Vec::new(),
+            max_age_jitter: None,
+            expiry: None,
+            fingerprinted: None,
+            fingerprint_paths: false,
+            version_params: Vec::new(),
+            default_policy: None,
+            permanent_redirect: None,
+            temporary_redirect: None,
+            range_policy: RangePolicy::Include,
+            overwrite: OverwriteBehavior::SkipIfPresent,
+            set_cookie: Downgrade::NoStore,
+            authorization: Downgrade::Ignore,
+            error: Downgrade::NoStore,
+            attachment: Downgrade::Ignore,
+            encoding_rules: Vec::new(),
+            smart_vary_encoding: false,
+            encoding_vary: true,
+            max_age_ceiling: None,
+            cache_tags: None,
+            targeted: Vec::new(),
+            precomputed: Precomputed::default(),
+        }
+    }
+
+    /// Create a new `CacheControlLayer` for the "browser revalidates, CDN
+    /// caches" pattern, using the default MIME types.
+    ///
+    /// Browsers are told the response is immediately stale (`max-age=0`),
+    /// while shared caches may store it for `edge`:
+    ///
+    /// ```text
+    /// Cache-Control: public, max-age=0, s-maxage=600
+    /// ```
+    pub fn cdn_only(edge: Duration) -> Self {
+        Self::new().with_directives(
+            CacheControlDirectives::new()
+                .public()
+                .with_max_age(Duration::ZERO)
+                .with_s_maxage(edge),
+        )
+    }
+
+    /// Create a new `CacheControlLayer` for sites behind Cloudflare, using the
+    /// default MIME types.
+    ///
+    /// Browsers may keep responses for `browser`, while Cloudflare keeps them
+    /// for `edge`. Cloudflare obeys `Cloudflare-CDN-Cache-Control` over
+    /// everything else and strips it before responding, so it can't leak to
+    /// other caches; `s-maxage` gives any other shared caches the same
+    /// lifetime:
+    ///
+    /// ```text
+    /// Cache-Control: public, max-age=60, s-maxage=86400
+    /// Cloudflare-CDN-Cache-Control: public, max-age=86400
+    /// ```
+    ///
+    /// Use [`CacheControlLayer::with_targeted`] to change the edge policy
+    /// later, or with [`TargetedField::Cdn`] to also address CDNs in front of
+    /// or behind Cloudflare.
+    pub fn cloudflare(browser: Duration, edge: Duration) -> Self {
+        Self::new()
+            .with_directives(
+                CacheControlDirectives::new()
+                    .public()
+                    .with_max_age(browser)
+                    .with_s_maxage(edge),
+            )
+            .with_targeted(TargetedField::Cloudflare, edge)
+    }
+
+    /// Create a new `CacheControlLayer` for assets that never change, using
+    /// the default MIME types.
+    ///
+    /// This emits the commonly recommended maximum of one year, marked as
+    /// `immutable` so browsers skip revalidation entirely:
+    ///
+    /// ```text
+    /// Cache-Control: public, max-age=31536000, immutable
+    /// ```
+    pub fn forever() -> Self {
+        Self::new().with_directives(immutable_year())
+    }
+
+    /// Create a new `CacheControlLayer` for a directory of static assets,
+    /// such as a bundler's output.
+    ///
+    /// Stylesheets, scripts, WebAssembly, images, and fonts are cached for
+    /// a year as `immutable`, like [`CacheControlLayer::forever`] does:
+    ///
+    /// ```text
+    /// Cache-Control: public, max-age=31536000, immutable
+    /// ```
+    ///
+    /// Assets that may change without their URL changing too, such as a
+    /// `favicon.ico` that isn't fingerprinted, need a shorter lifetime; add a
+    /// rule for them.
+    pub fn static_assets() -> Self {
+        Self::forever().with_shared_mime_types(Arc::new(ASSET_MIME_TYPES))
+    }
+
+    /// Create a new `CacheControlLayer` for a JSON API, whose responses are
+    /// usually specific to the request and must not be reused:
+    ///
+    /// ```text
+    /// Cache-Control: no-store
+    /// ```
+    ///
+    /// Add rules or use [`Cached`](crate::Cached) for the endpoints that are
+    /// safe to cache.
+    pub fn api() -> Self {
+        Self::new()
+            .with_mime_types(&[MimeType::JSON])
+            .with_directives(CacheControlDirectives::new().no_store())
+    }
+
+    /// Create a new `CacheControlLayer` for a single-page app.
+    ///
+    /// The HTML that bootstraps the app is revalidated on every request, so
+    /// a deploy takes effect immediately, while the assets it references are
+    /// cached as they are by [`CacheControlLayer::static_assets`]:
+    ///
+    /// ```text
+    /// text/html: Cache-Control: no-cache
+    /// assets:    Cache-Control: public, max-age=31536000, immutable
+    /// ```
+    ///
+    /// This assumes the bundler fingerprints every asset's filename (e.g.
+    /// `index.3fa9c2.js`), as Vite and webpack do by default.
+    pub fn spa() -> Self {
+        Self::static_assets().with_policy(MimeType::HTML, CacheControlDirectives::new().no_cache())
+    }
+
+    /// Check the layer's configuration, returning it if it's sound.
+    ///
+    /// Returns [`CacheControlError::NoPolicies`] if the layer can never match
+    /// a response, or the first error [`CacheControlDirectives::validate`]
+    /// finds in the directives of a MIME type, policy, or rule.
+    ///
+    /// Directives picked at runtime, by handlers, policy functions, or
+    /// resolvers, can't be checked here. With the `tracing` feature enabled,
+    /// the layer logs a warning when it emits invalid ones instead.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlDirectives, CacheControlError, CacheControlLayer, MimeType};
+    ///
+    /// let layer = CacheControlLayer::new()
+    ///     .with_mime_types(&[MimeType::CSS])
+    ///     .with_max_age(Duration::from_secs(86400))
+    ///     .build()?;
+    ///
+    /// // `no-store` forbids caching, so a `max-age` makes no sense.
+    /// let error = CacheControlLayer::new()
+    ///     .with_directives(
+    ///         CacheControlDirectives::new()
+    ///             .no_store()
+    ///             .with_max_age(Duration::from_secs(60)),
+    ///     )
+    ///     .build()
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(error, CacheControlError::ConflictingDirectives);
+    /// # Ok::<(), CacheControlError>(())
+    /// ```
+    pub fn build(self) -> Result<Self, CacheControlError> {
+        if self.is_empty() {
+            return Err(CacheControlError::NoPolicies);
+        }
+
+        let directives = (!self.mime_types.is_empty()).then_some(&self.directives);
+        let policies = self.policies.iter().map(|(_, directives)| directives);
+        let rules = self.rules.iter().map(|rule| &rule.directives);
+
+        let directives = directives
+            .into_iter()
+            .chain(&self.fingerprinted)
+            .chain(&self.default_policy)
+            .chain(&self.permanent_redirect)
+            .chain(&self.temporary_redirect);
+
+        for directives in directives.chain(policies).chain(rules) {
+            directives.validate()?;
+        }
+
+        Ok(self)
+    }
+
+    /// Create a new `CacheControlLayer` from a [`CacheControlConfig`], such as
+    /// one loaded from a configuration file.
+    ///
+    /// The configuration is applied on top of [`CacheControlLayer::new`], in
+    /// the same way as the builder method of the same name.
+    ///
+    /// [`CacheControlConfig`]: crate::config::CacheControlConfig
+    #[cfg(feature = "serde")]
+    pub fn from_config(config: CacheControlConfig) -> Self {
+        let mut layer = Self::new();
+
+        if let Some(mime_types) = config.mime_types {
+            layer = layer.with_mime_types(&mime_types);
+        }
+
+        if let Some(directives) = config.directives {
+            layer = layer.with_directives(directives);
+        }
+
+        for policy in config.policies {
+            layer = layer.with_policy(policy.mime, policy.directives);
+        }
+
+        for rule in config.rules {
+            layer = match &rule.name {
+                Some(name) => layer.named_rule(name, rule.matcher(), rule.directives),
+                None => layer.rule(rule.matcher(), rule.directives),
+            };
+        }
+
+        if let Some(methods) = config.methods {
+            layer = layer.with_methods(&methods);
+        }
+
+        if !config.vary.is_empty() {
+            layer = layer.with_vary(&config.vary);
+        }
+
+        if let Some(behavior) = config.overwrite {
+            layer = layer.with_overwrite_behavior(behavior);
+        }
+
+        if let Some(policy) = config.range_policy {
+            layer = layer.with_range_policy(policy);
+        }
+
+        if let Some(downgrade) = config.set_cookie_downgrade {
+            layer = layer.with_set_cookie_downgrade(downgrade);
+        }
+
+        if let Some(downgrade) = config.authorization_downgrade {
+            layer = layer.with_authorization_downgrade(downgrade);
+        }
+
+        if let Some(downgrade) = config.error_downgrade {
+            layer = layer.with_error_downgrade(downgrade);
+        }
+
+        if let Some(downgrade) = config.attachment_downgrade {
+            layer = layer.with_attachment_downgrade(downgrade);
+        }
+
+        if let Some(ceiling) = config.max_age_ceiling {
+            layer = layer.with_max_age_ceiling(ceiling);
+        }
+
+        if config.fingerprinting {
+            layer = layer.with_fingerprinting();
+        }
+
+        if !config.version_params.is_empty() {
+            let params: Vec<_> = config.version_params.iter().map(String::as_str).collect();
+            layer = layer.with_version_params(&params);
+        }
+
+        if config.expires {
+            layer = layer.with_expires();
+        }
+
+        if config.pragma {
+            layer = layer.with_pragma();
+        }
+
+        if config.dry_run {
+            layer = layer.with_dry_run();
+        }
+
+        if config.rule_header {
+            layer = layer.with_rule_header();
+        }
+
+        if let Some(directives) = config.default_policy {
+            layer = layer.with_default_policy(directives);
+        }
+
+        if let Some(redirects) = config.redirects {
+            layer = layer.with_redirect_policy(redirects.permanent, redirects.temporary);
+        }
+
+        layer
+    }
+
+    /// Set the MIME types that should have `Cache-Control` headers set.
+    ///
+    /// The list is copied into the layer, so it can come from configuration
+    /// loaded at runtime:
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, MimeType};
+    ///
+    /// let mime_types: Vec<MimeType> = ["css", "js"]
+    ///     .into_iter()
+    ///     .map(MimeType::from_extension)
+    ///     .collect();
+    ///
+    /// let layer = CacheControlLayer::new().with_mime_types(&mime_types);
+    /// ```
+    pub fn with_mime_types(mut self, mime_types: &[MimeType]) -> Self {
+        self.mime_types = mime_types.into();
+        self
+    }
+
+    /// Set the MIME types that should have `Cache-Control` headers set, from
+    /// [`mime::Mime`](::mime::Mime) values.
+    ///
+    /// ```rust
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// let layer = CacheControlLayer::new().with_mimes(&[mime::TEXT_CSS, mime::IMAGE_PNG]);
+    /// ```
+    #[cfg(feature = "mime")]
+    pub fn with_mimes(mut self, mimes: &[::mime::Mime]) -> Self {
+        self.mime_types = mimes.iter().map(MimeType::from).collect();
+        self
+    }
+
+    /// Set the MIME types that should have `Cache-Control` headers set, from
+    /// a shared list.
+    ///
+    /// Unlike [`CacheControlLayer::with_mime_types`], this doesn't copy the
+    /// list, so several layers can share one.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use axum_cc::{CacheControlLayer, MimeType};
+    ///
+    /// let mime_types: Arc<[MimeType]> = vec![MimeType::CSS, MimeType::JS].into();
+    /// let layer = CacheControlLayer::new().with_shared_mime_types(mime_types);
+    /// ```
+    pub fn with_shared_mime_types(mut self, mime_types: Arc<[MimeType]>) -> Self {
+        self.mime_types = mime_types;
+        self
+    }
+
+    /// Set the `max-age` value for the `Cache-Control` header.
+    ///
+    /// Use [`parse_duration`](crate::parse_duration) to write the lifetime
+    /// as a string like `"30d"`.
+    pub fn with_max_age(mut self, max_age: impl Into<Duration>) -> Self {
+        self.directives.set_max_age(Some(max_age.into()));
+        self
+    }
+
+    /// Set whether shared caches may store matched responses.
+    ///
+    /// The default is [`Visibility::Public`]. Use [`Visibility::Private`] for
+    /// responses behind authentication, so that they can be cached by the
+    /// browser but not by a CDN or proxy:
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, Visibility};
+    ///
+    /// // Cache-Control: private, max-age=31536000
+    /// let layer = CacheControlLayer::new().with_visibility(Visibility::Private);
+    /// ```
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.directives = self.directives.with_visibility(visibility);
+        self
+    }
+
+    /// Mark matched responses as `immutable`, so browsers skip revalidation
+    /// while they are fresh.
+    ///
+    /// Only use this for fingerprinted assets (e.g. `app.3fa9c2.js`), whose
+    /// content can never change without their URL changing too.
+    pub fn immutable(mut self) -> Self {
+        self.directives = self.directives.immutable();
+        self
+    }
+
+    /// Set the `s-maxage` value for the `Cache-Control` header, giving shared
+    /// caches (e.g. CDNs) a different lifetime than browsers.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// // Cache-Control: public, max-age=60, s-maxage=86400
+    /// let layer = CacheControlLayer::new()
+    ///     .with_max_age(Duration::from_secs(60))
+    ///     .with_s_maxage(Duration::from_secs(86400));
+    /// ```
+    pub fn with_s_maxage(mut self, s_maxage: impl Into<Duration>) -> Self {
+        self.directives.set_s_maxage(Some(s_maxage.into()));
+        self
+    }
+
+    /// Mark matched responses as `must-revalidate`, so no cache serves them
+    /// once they are stale without checking with the origin first.
+    pub fn must_revalidate(mut self) -> Self {
+        self.directives = self.directives.must_revalidate();
+        self
+    }
+
+    /// Mark matched responses as `proxy-revalidate`, which is like
+    /// [`CacheControlLayer::must_revalidate`] but only applies to shared
+    /// caches.
+    pub fn proxy_revalidate(mut self) -> Self {
+        self.directives = self.directives.proxy_revalidate();
+        self
+    }
+
+    /// Mark matched responses as `no-transform`, so proxies (e.g. mobile
+    /// carriers) don't recompress or otherwise alter them.
+    pub fn no_transform(mut self) -> Self {
+        self.directives = self.directives.no_transform();
+        self
+    }
+
+    /// Set the directives emitted in the `Cache-Control` header, replacing
+    /// the default `public, max-age=N`.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer};
+    ///
+    /// // Cache-Control: private, max-age=600, must-revalidate
+    /// let layer = CacheControlLayer::new().with_directives(
+    ///     CacheControlDirectives::new()
+    ///         .private()
+    ///         .with_max_age(Duration::from_secs(600))
+    ///         .must_revalidate(),
+    /// );
+    /// ```
+    pub fn with_directives(mut self, directives: CacheControlDirectives) -> Self {
+        self.directives = directives;
+        self
+    }
+
+    /// Set the directives for a specific MIME type, replacing the layer's
+    /// directives for responses of that type.
+    ///
+    /// A [`Duration`] converts to `public, max-age=N`; pass a
+    /// [`CacheControlDirectives`] for anything else.
+    ///
+    /// The MIME type does not need to be in the layer's list of MIME types;
+    /// adding a policy for it is enough for it to be matched. Calling this
+    /// again for the same MIME type replaces the previous policy.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, MimeType};
+    ///
+    /// const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+    ///
+    /// // Fonts and images for a year (the default), stylesheets for a day,
+    /// // scripts for a minute (served stale for up to a day while they are
+    /// // revalidated), and HTML never.
+    /// let layer = CacheControlLayer::new()
+    ///     .with_policy(MimeType::CSS, DAY)
+    ///     .with_policy(
+    ///         MimeType::JS,
+    ///         CacheControlDirectives::new()
+    ///             .public()
+    ///             .with_max_age(Duration::from_secs(60))
+    ///             .with_stale_while_revalidate(DAY),
+    ///     )
+    ///     .with_policy(MimeType::HTML, CacheControlDirectives::new().no_store());
+    /// ```
+    ///
+    /// Policies without a `max-age` (such as `no-store` or `no-cache`) are
+    /// emitted as-is; options that adjust `max-age`, like
+    /// [`CacheControlLayer::with_encoding_rules`], only apply to policies
+    /// that have one.
+    pub fn with_policy(
+        mut self,
+        mime: MimeType,
+        directives: impl Into<CacheControlDirectives>,
+    ) -> Self {
+        let directives = directives.into();
+
+        match self.policies.iter_mut().find(|(m, _)| *m == mime) {
+            Some((_, existing)) => *existing = directives,
+            None => self.policies.push((mime, directives)),
+        }
+
+        self
+    }
+
+    /// Emit `directives` on responses that no rule, policy, or MIME type of
+    /// the layer matches, including those without a `Content-Type`.
+    ///
+    /// Without a default policy, such responses are left untouched, and
+    /// caches are free to store them heuristically. To make sure nothing is
+    /// cached unless a policy says so:
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer};
+    ///
+    /// let layer = CacheControlLayer::static_assets()
+    ///     .with_default_policy(CacheControlDirectives::new().no_store());
+    /// ```
+    ///
+    /// Responses the layer skips for other reasons, such as their request
+    /// method, are still left untouched.
+    pub fn with_default_policy(mut self, directives: impl Into<CacheControlDirectives>) -> Self {
+        self.default_policy = Some(directives.into());
+        self
+    }
+
+    /// Emit `permanent` on permanent redirects (`301` and `308`), and
+    /// `temporary` on temporary ones (`302`, `303`, and `307`).
+    ///
+    /// Redirects rarely carry a `Content-Type`, so without a redirect policy
+    /// they only get one from a rule. A cached temporary redirect keeps
+    /// sending users to the old location after it changes, so `temporary` is
+    /// usually `no-store`:
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer};
+    ///
+    /// let layer = CacheControlLayer::new().with_redirect_policy(
+    ///     CacheControlDirectives::new().public().with_max_age(Duration::from_secs(60 * 60 * 24)),
+    ///     CacheControlDirectives::new().no_store(),
+    /// );
+    /// ```
+    ///
+    /// Browsers may keep permanent redirects for a very long time, so even
+    /// those are better given a lifetime you can live with if the redirect
+    /// turns out to be wrong. Rules still take precedence.
+    pub fn with_redirect_policy(
+        mut self,
+        permanent: impl Into<CacheControlDirectives>,
+        temporary: impl Into<CacheControlDirectives>,
+    ) -> Self {
+        self.permanent_redirect = Some(permanent.into());
+        self.temporary_redirect = Some(temporary.into());
+        self
+    }
+
+    /// Add a rule that emits `directives` for responses matching `matcher`.
+    ///
+    /// Rules are checked in the order they were added, and the first one that
+    /// matches wins. Responses that match no rule fall back to the layer's
+    /// MIME types and policies.
+    ///
+    /// A rule emits its own directives in full, so each rule can carry its
+    /// own `stale-while-revalidate` and `stale-if-error` windows; those set
+    /// on the layer itself only apply to responses no rule matches.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, Matcher, MimeType};
+    ///
+    /// const YEAR: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+    ///
+    /// let layer = CacheControlLayer::default()
+    ///     .rule(
+    ///         Matcher::path_prefix("/assets"),
+    ///         CacheControlDirectives::from(YEAR).immutable(),
+    ///     )
+    ///     .rule(Matcher::path_prefix("/api"), CacheControlDirectives::new().no_store())
+    ///     .rule(Matcher::mime(MimeType::HTML), CacheControlDirectives::new().no_cache());
+    /// ```
+    pub fn rule(mut self, matcher: Matcher, directives: impl Into<CacheControlDirectives>) -> Self {
+        self.rules.push(Rule {
+            matcher,
+            directives: directives.into(),
+            surrogate_keys: None,
+            vary: Vec::new(),
+            name: None,
+        });
+        self
+    }
+
+    /// Add a rule like [`CacheControlLayer::rule`], named `name` in the
+    /// header set by [`CacheControlLayer::with_rule_header`].
+    ///
+    /// Names that aren't valid header values are ignored.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, Matcher};
+    ///
+    /// let layer = CacheControlLayer::new()
+    ///     .named_rule("api", Matcher::path_prefix("/api"), CacheControlDirectives::new().no_store())
+    ///     .with_rule_header();
+    /// ```
+    pub fn named_rule(
+        mut self,
+        name: &str,
+        matcher: Matcher,
+        directives: impl Into<CacheControlDirectives>,
+    ) -> Self {
+        self.rules.push(Rule {
+            matcher,
+            directives: directives.into(),
+            surrogate_keys: None,
+            vary: Vec::new(),
+            name: HeaderValue::from_str(name).ok(),
+        });
+        self
+    }
+
+    /// Add a rule like [`CacheControlLayer::rule`], which also attaches the
+    /// `Surrogate-Key` values returned by `keys` to the responses it matches,
+    /// so that Fastly or Varnish can purge them by key.
+    ///
+    /// The keys are added to any set with
+    /// [`CacheControlLayer::with_cache_tags`]. Keys that are empty, contain
+    /// spaces, or contain characters that aren't valid in a header are
+    /// dropped, and the others kept.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlLayer, Matcher};
+    ///
+    /// // Surrogate-Key: products product-42
+    /// let layer = CacheControlLayer::default().rule_with_surrogate_keys(
+    ///     Matcher::path_prefix("/products"),
+    ///     Duration::from_secs(60 * 60),
+    ///     |req, _| {
+    ///         let id = req.uri.path().rsplit('/').next().unwrap_or_default();
+    ///         vec!["products".to_owned(), format!("product-{id}")]
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// Like a policy function, a key function makes the layer keep a copy of
+    /// each request's head until the response is ready.
+    pub fn rule_with_surrogate_keys<F>(
+        mut self,
+        matcher: Matcher,
+        directives: impl Into<CacheControlDirectives>,
+        keys: F,
+    ) -> Self
+    where
+        F: Fn(&request::Parts, &response::Parts) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.rules.push(Rule {
+            matcher,
+            directives: directives.into(),
+            surrogate_keys: Some(KeyFn(Arc::new(keys))),
+            vary: Vec::new(),
+            name: None,
+        });
+        self
+    }
+
+    /// Add a rule like [`CacheControlLayer::rule`], which also adds `names`
+    /// to the `Vary` header of the responses it matches, keeping any values
+    /// already present.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlLayer, Matcher};
+    /// use http::header::ACCEPT_LANGUAGE;
+    ///
+    /// // Vary: accept-language, only for pages under /docs
+    /// let layer = CacheControlLayer::default().rule_with_vary(
+    ///     Matcher::path_prefix("/docs"),
+    ///     Duration::from_secs(60 * 60),
+    ///     &[ACCEPT_LANGUAGE],
+    /// );
+    /// ```
+    pub fn rule_with_vary(
+        mut self,
+        matcher: Matcher,
+        directives: impl Into<CacheControlDirectives>,
+        names: &[HeaderName],
+    ) -> Self {
+        self.rules.push(Rule {
+            matcher,
+            directives: directives.into(),
+            surrogate_keys: None,
+            vary: names.to_vec(),
+            name: None,
+        });
+        self
+    }
+
+    /// Pick the directives for responses with a function, for logic the
+    /// built-in matchers can't express.
+    ///
+    /// The function is called with the request and response parts before any
+    /// rules or MIME types are considered. If it returns `None`, the response
+    /// falls back to those as usual.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer};
+    ///
+    /// let layer = CacheControlLayer::new().with_policy_fn(|req, res| {
+    ///     // Never cache per-tenant error pages.
+    ///     (req.headers.contains_key("x-tenant") && res.status.is_server_error())
+    ///         .then(|| CacheControlDirectives::new().no_store())
+    /// });
+    /// ```
+    ///
+    /// Setting a policy function makes the layer keep a copy of each
+    /// request's head until the response is ready.
+    pub fn with_policy_fn<F, P>(mut self, f: F) -> Self
+    where
+        F: Fn(&request::Parts, &response::Parts) -> Option<P> + Send + Sync + 'static,
+        P: Into<CacheControlDirectives>,
+    {
+        self.policy_fn = Some(PolicyFn(Arc::new(move |req, res| {
+            f(req, res).map(Into::into)
+        })));
+        self
+    }
+
+    /// Pick the directives for responses asynchronously. See
+    /// [`CachePolicyResolver`] for details.
+    ///
+    /// Like a policy function, a resolver makes the layer keep a copy of each
+    /// request's head until the response is ready. The resolver takes
+    /// precedence over the policy function.
+    pub fn with_resolver(mut self, resolver: impl CachePolicyResolver) -> Self {
+        self.resolver = Some(Resolver(Arc::new(resolver)));
+        self
+    }
+
+    /// Call `f` with the [`Outcome`] of every response the layer inspects,
+    /// for example to count how many responses get a policy in production.
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// use axum_cc::{CacheControlLayer, Outcome};
+    ///
+    /// static APPLIED: AtomicU64 = AtomicU64::new(0);
+    /// static SKIPPED: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// let layer = CacheControlLayer::new().with_observer(|outcome| {
+    ///     let counter = match outcome {
+    ///         Outcome::Applied { .. } => &APPLIED,
+    ///         Outcome::Skipped(_) => &SKIPPED,
+    ///     };
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// ```
+    ///
+    /// Skipped responses can be broken down by [`SkipReason`]:
+    ///
+    /// ```rust
+    /// use std::{collections::HashMap, sync::Mutex};
+    ///
+    /// use axum_cc::{CacheControlLayer, Outcome, SkipReason};
+    ///
+    /// static SKIPPED: Mutex<Option<HashMap<SkipReason, u64>>> = Mutex::new(None);
+    ///
+    /// let layer = CacheControlLayer::new().with_observer(|outcome| {
+    ///     if let Outcome::Skipped(reason) = outcome {
+    ///         let mut skipped = SKIPPED.lock().unwrap();
+    ///         *skipped.get_or_insert_with(HashMap::new).entry(*reason).or_default() += 1;
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// With the `metrics` feature, the layer records these as
+    /// `cache_control_applied_total` and `cache_control_skipped_total`
+    /// counters itself, without an observer.
+    ///
+    /// Responses whose policy was picked by a handler, resolver, or policy
+    /// function are reported as applied without a rule. The function runs on
+    /// every response, so keep it cheap.
+    pub fn with_observer<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Outcome<'_>) + Send + Sync + 'static,
+    {
+        self.observer = Some(Observer(Arc::new(f)));
+        self
+    }
+
+    /// Set the `stale-while-revalidate` value for the `Cache-Control` header,
+    /// allowing caches to serve a stale response for this long while they
+    /// revalidate it in the background.
+    pub fn with_stale_while_revalidate(mut self, duration: impl Into<Duration>) -> Self {
+        self.directives = self.directives.with_stale_while_revalidate(duration.into());
+        self
+    }
+
+    /// Set the `stale-if-error` value for the `Cache-Control` header,
+    /// allowing caches to serve a stale response for this long when the
+    /// origin fails to respond (e.g. with a `5xx`).
+    pub fn with_stale_if_error(mut self, duration: impl Into<Duration>) -> Self {
+        self.directives = self.directives.with_stale_if_error(duration.into());
+        self
+    }
+
+    /// Never emit a `max-age` or `s-maxage` larger than `ceiling`.
+    ///
+    /// Some CDNs silently cap (or ignore) lifetimes above a plan-specific
+    /// maximum; clamping makes the emitted header match what the CDN will
+    /// actually do. With the `tracing` feature enabled, clamping is logged at
+    /// the `DEBUG` level.
+    pub fn with_max_age_ceiling(mut self, ceiling: Duration) -> Self {
+        self.max_age_ceiling = Some(ceiling);
+        self
+    }
+
+    /// Reduce the emitted `max-age` by the response's `Age` header, if any.
+    ///
+    /// Behind multiple tiers of caches, this keeps the total freshness
+    /// lifetime bounded by the configured `max-age`. If the response is older
+    /// than `max-age`, `max-age=0` is emitted.
+    pub fn with_age_decay(mut self) -> Self {
+        self.age_decay = true;
+        self
+    }
+
+    /// Set an `Age` header on matched responses that carry a [`StoredAt`]
+    /// extension, counting the seconds since they were stored.
+    ///
+    /// Use this when handlers serve responses from a server-side cache, so
+    /// that downstream caches don't consider them fresh for longer than
+    /// intended. An `Age` header that is already present, for example from a
+    /// cache the layer wraps, is passed through untouched. Combined with
+    /// [`CacheControlLayer::with_age_decay`], the computed age is also
+    /// subtracted from `max-age`.
+    pub fn with_age(mut self) -> Self {
+        self.age = true;
+        self
+    }
+
+    /// Select the `max-age` value based on how long ago the response was
+    /// last modified, according to its `Last-Modified` header.
+    ///
+    /// Each bucket is a pair of `(modified within, max-age)`, checked in
+    /// order; the first bucket whose window contains the response's age is
+    /// used. Responses older than every window, and responses without a
+    /// (valid) `Last-Modified` header, use the regular `max-age`.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// const HOUR: Duration = Duration::from_secs(60 * 60);
+    /// const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+    ///
+    /// // Files changed in the last day are cached for a minute, files changed
+    /// // in the last week for an hour, and everything else for a year.
+    /// let layer = CacheControlLayer::new().with_mtime_buckets(&[
+    ///     (DAY, Duration::from_secs(60)),
+    ///     (Duration::from_secs(7 * 24 * 60 * 60), HOUR),
+    /// ]);
+    /// ```
+    pub fn with_mtime_buckets(mut self, buckets: &[(Duration, Duration)]) -> Self {
+        self.mtime_buckets = buckets.to_vec();
+        self
+    }
+
+    /// Shorten the emitted `max-age` by a random amount of up to `jitter`,
+    /// picked anew for each response.
+    ///
+    /// When a deploy makes a whole fleet of clients fetch an asset at once,
+    /// their copies would otherwise all expire at once too, and revalidate in
+    /// a burst. Jitter spreads their expiry out over the window, and never
+    /// makes a response fresh for longer than its policy allows.
+    ///
+    /// The amount comes from the standard library's hasher seeding rather
+    /// than a proper random number generator, so it is only roughly uniform.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// // Cache-Control: public, max-age=N, with N between 3000 and 3600
+    /// let layer = CacheControlLayer::new()
+    ///     .with_max_age(Duration::from_secs(3600))
+    ///     .with_max_age_jitter(Duration::from_secs(600));
+    /// ```
+    pub fn with_max_age_jitter(mut self, jitter: Duration) -> Self {
+        self.max_age_jitter = Some(jitter).filter(|jitter| !jitter.is_zero());
+        self
+    }
+
+    /// Cache responses for fingerprinted file names, such as `app.3fa9c2.js`,
+    /// for a year as `immutable`, whatever the policy for their MIME type.
+    ///
+    /// Bundlers put a hash of each asset's content in its file name, so a
+    /// changed asset always has a new URL, and the old one can be cached
+    /// forever. Assets without a hash, like `favicon.ico`, keep the layer's
+    /// policy, which should then be short enough for changes to show up:
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// // /app.3fa9c2.js: Cache-Control: public, max-age=31536000, immutable
+    /// // /app.js:        Cache-Control: public, max-age=3600
+    /// let layer = CacheControlLayer::new()
+    ///     .with_max_age(Duration::from_secs(3600))
+    ///     .with_fingerprinting();
+    /// ```
+    ///
+    /// Only responses the layer matches by MIME type are affected; rules
+    /// still take precedence. See [`Matcher::with_fingerprinted`] for what
+    /// counts as a fingerprinted file name.
+    pub fn with_fingerprinting(mut self) -> Self {
+        self.fingerprint_paths = true;
+        self.fingerprinted = Some(immutable_year());
+        self
+    }
+
+    /// Cache responses to requests with one of the given query parameters,
+    /// such as `?v=3` or `?hash=3fa9c2`, for a year as `immutable`, whatever
+    /// the policy for their MIME type.
+    ///
+    /// This is the cache-busting counterpart of
+    /// [`CacheControlLayer::with_fingerprinting`], for pages that reference
+    /// assets as `/app.js?v=3` and bump the parameter when they change. The
+    /// same path without the parameter keeps the layer's policy:
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// // /app.js?v=3: Cache-Control: public, max-age=31536000, immutable
+    /// // /app.js:     Cache-Control: public, max-age=3600
+    /// let layer = CacheControlLayer::new()
+    ///     .with_max_age(Duration::from_secs(3600))
+    ///     .with_version_params(&["v", "hash"]);
+    /// ```
+    ///
+    /// Parameters with an empty value don't count. As with fingerprinting,
+    /// only responses the layer matches by MIME type are affected, and rules
+    /// take precedence.
+    pub fn with_version_params(mut self, params: &[&str]) -> Self {
+        self.version_params = params.iter().map(|param| param.to_string()).collect();
+        self.fingerprinted = Some(immutable_year());
+        self
+    }
+
+    /// Count `max-age` down to a deadline, instead of starting it afresh
+    /// for every response.
+    ///
+    /// Every client then expires its copy at the same, known time, such as
+    /// right after a nightly content refresh. Only policies with a `max-age`
+    /// are affected, and a deadline that has passed emits `max-age=0`.
+    /// Encoding rules and mtime buckets take precedence.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::{CacheControlLayer, Expiry};
+    ///
+    /// // Content is regenerated every night at 02:00 UTC.
+    /// let layer = CacheControlLayer::new()
+    ///     .with_expiry(Expiry::DailyAt(Duration::from_secs(2 * 60 * 60)));
+    /// ```
+    pub fn with_expiry(mut self, expiry: Expiry) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// Select the `max-age` value based on the response's `Content-Encoding`.
+    ///
+    /// Each rule pairs an encoding (e.g. `br` or `gzip`) with the `max-age` to
+    /// use for responses in that encoding. Unencoded responses, and encodings
+    /// without a rule, use the regular `max-age`. Since the same resource is
+    /// then cached differently per encoding, `Accept-Encoding` is added to
+    /// the `Vary` header.
+    ///
+    /// Encoding rules take precedence over
+    /// [`CacheControlLayer::with_mtime_buckets`].
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// let layer = CacheControlLayer::new().with_encoding_rules(&[
+    ///     ("br", Duration::from_secs(60 * 60 * 24)),
+    ///     ("gzip", Duration::from_secs(60 * 60)),
+    /// ]);
+    /// ```
+    pub fn with_encoding_rules(mut self, rules: &[(&str, Duration)]) -> Self {
+        self.encoding_rules = rules
+            .iter()
+            .map(|(encoding, max_age)| (encoding.to_string(), *max_age))
+            .collect();
+        self
+    }
+
+    /// Set the `max-age` value for the `Cache-Control` header, in seconds.
+    ///
+    /// Unlike [`CacheControlLayer::with_max_age`], this can never produce
+    /// `max-age=0`.
+    pub fn with_max_age_nonzero(mut self, max_age: NonZeroU64) -> Self {
+        self.directives
+            .set_max_age(Some(Duration::from_secs(max_age.get())));
+        self
+    }
+
+    /// Set the `Cache-Control` value to use based on the request's
+    /// `Sec-Fetch-Site` header.
+    ///
+    /// When a matched response was requested from a context listed in
+    /// `rules`, the paired directives are emitted verbatim instead of the
+    /// default `public, max-age=N`. Requests without the header, or from a
+    /// context not listed, use the default. Entries that aren't valid header
+    /// values are ignored.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, FetchSite};
+    ///
+    /// // Don't let cross-site embeds keep a copy of our assets.
+    /// let layer = CacheControlLayer::new()
+    ///     .with_fetch_site_rules(&[(FetchSite::CrossSite, "no-store")]);
+    /// ```
+    pub fn with_fetch_site_rules(mut self, rules: &[(FetchSite, &str)]) -> Self {
+        self.fetch_site_rules = rules
+            .iter()
+            .filter_map(|(site, directives)| Some((*site, HeaderValue::from_str(directives).ok()?)))
+            .collect();
+        self
+    }
+
+    /// Only set `Cache-Control` headers on responses that carry the given
+    /// header, in addition to a matching `Content-Type`.
+    ///
+    /// This is useful for only caching responses produced by a particular
+    /// service. For example, `tower_http::services::ServeDir` sets
+    /// `Accept-Ranges: bytes` on every file it serves, but not on the
+    /// responses produced by its fallback, so a `404` page that happens to be
+    /// `text/css` will not be cached:
+    ///
+    /// ```rust
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// let layer = CacheControlLayer::new().with_marker_header("accept-ranges");
+    /// ```
+    ///
+    /// See `examples/serve_dir.rs` for a complete example.
+    pub fn with_marker_header(mut self, name: &str) -> Self {
+        self.marker_header = Some(name.to_owned());
+        self
+    }
+
+    /// Don't set `Cache-Control` headers on responses that carry any of the
+    /// given headers.
+    ///
+    /// ```rust
+    /// use axum_cc::CacheControlLayer;
+    /// use http::header::{HeaderName, SET_COOKIE, WWW_AUTHENTICATE};
+    ///
+    /// const SKIP: [HeaderName; 2] = [SET_COOKIE, WWW_AUTHENTICATE];
+    ///
+    /// let layer = CacheControlLayer::new().with_skip_if_headers(&SKIP);
+    /// ```
+    pub fn with_skip_if_headers(mut self, headers: &[HeaderName]) -> Self {
+        self.skip_if_headers = headers.to_vec();
+        self
+    }
+
+    /// Only set `Cache-Control` headers on responses to requests with one of
+    /// the given methods.
+    ///
+    /// By default, only responses to `GET` and `HEAD` requests are matched.
+    ///
+    /// ```rust
+    /// use axum_cc::CacheControlLayer;
+    /// use http::Method;
+    ///
+    /// // Our search endpoint is a cacheable `POST`.
+    /// const METHODS: [Method; 3] = [Method::GET, Method::HEAD, Method::POST];
+    ///
+    /// let layer = CacheControlLayer::new().with_methods(&METHODS);
+    /// ```
+    ///
+    /// Rules can further narrow this down with [`Matcher::with_method`].
+    pub fn with_methods(mut self, methods: &[Method]) -> Self {
+        self.methods = Some(methods.to_vec());
+        self
+    }
+
+    /// Set `Cache-Control` headers on responses to requests with any method,
+    /// not just `GET` and `HEAD`.
+    pub fn with_any_method(mut self) -> Self {
+        self.methods = None;
+        self
+    }
+
+    /// Guess the MIME type of responses without a `Content-Type` header from
+    /// the request path's extension, as [`MimeType::from_path`] does.
+    ///
+    /// Some services, such as reverse proxies and custom file handlers, omit
+    /// `Content-Type`, which otherwise means their responses never match a
+    /// MIME type. Responses with a malformed `Content-Type` still match
+    /// nothing.
+    ///
+    /// ```rust
+    /// use axum_cc::CacheControlLayer;
+    ///
+    /// // `GET /app.js` without a `Content-Type` is treated as JavaScript.
+    /// let layer = CacheControlLayer::new().with_mime_from_path();
+    /// ```
+    pub fn with_mime_from_path(mut self) -> Self {
+        self.mime_from_path = true;
+        self
+    }
+
+    /// Add `names` to the `Vary` header of every response that has
+    /// `Cache-Control` set, keeping any values already present.
+    ///
+    /// List every request header that changes the response, or shared caches
+    /// may serve one client's representation to another. To vary only some
+    /// responses, use [`CacheControlLayer::rule_with_vary`] or
+    /// [`CacheControlLayer::with_policy_vary`] instead.
+    ///
+    /// ```rust
+    /// use axum_cc::CacheControlLayer;
+    /// use http::header::{ACCEPT, ACCEPT_LANGUAGE};
+    ///
+    /// let layer = CacheControlLayer::new().with_vary(&[ACCEPT, ACCEPT_LANGUAGE]);
+    /// ```
+    pub fn with_vary(mut self, names: &[HeaderName]) -> Self {
+        self.vary.extend_from_slice(names);
+        self
+    }
+
+    /// Add `names` to the `Vary` header of responses that get the policy set
+    /// for `mime` with [`CacheControlLayer::with_policy`], keeping any values
+    /// already present.
+    ///
+    /// Responses of that MIME type that a rule or a handler picks the policy
+    /// for don't get them.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, MimeType};
+    /// use http::header::ACCEPT_LANGUAGE;
+    ///
+    /// let layer = CacheControlLayer::new()
+    ///     .with_policy(MimeType::HTML, CacheControlDirectives::new().no_cache())
+    ///     .with_policy_vary(MimeType::HTML, &[ACCEPT_LANGUAGE]);
+    /// ```
+    pub fn with_policy_vary(mut self, mime: MimeType, names: &[HeaderName]) -> Self {
+        match self.policy_vary.iter_mut().find(|(m, _)| *m == mime) {
+            Some((_, existing)) => existing.extend_from_slice(names),
+            None => self.policy_vary.push((mime, names.to_vec())),
+        }
+
+        self
+    }
+
+    /// Add `Accept` to the `Vary` header of every response that has
+    /// `Cache-Control` set.
+    ///
+    /// Use this when handlers choose a representation based on the request's
+    /// `Accept` header, so that caches don't serve a representation other
+    /// than the one the client asked for.
+    pub fn with_content_negotiation_vary(mut self) -> Self {
+        self.content_negotiation_vary = true;
+        self
+    }
+
+    /// Set how responses to range requests are treated.
+    ///
+    /// Media servers (including `tower_http::services::ServeDir`) advertise
+    /// `Accept-Ranges: bytes` and answer range requests with `206 Partial
+    /// Content`. With [`RangePolicy::SkipPartial`], only the full `200`
+    /// response gets `Cache-Control`.
+    pub fn with_range_policy(mut self, policy: RangePolicy) -> Self {
+        self.range_policy = policy;
+        self
+    }
+
+    /// Set what happens when a response already has a `Cache-Control` header.
+    ///
+    /// By default ([`OverwriteBehavior::SkipIfPresent`]), headers set by
+    /// handlers are left alone. Use [`OverwriteBehavior::Always`] to enforce
+    /// the layer's policy regardless, or [`OverwriteBehavior::Merge`] to
+    /// combine the two.
+    pub fn with_overwrite_behavior(mut self, behavior: OverwriteBehavior) -> Self {
+        self.overwrite = behavior;
+        self
+    }
+
+    /// Set how matched responses that carry a `Set-Cookie` header are treated.
+    ///
+    /// A response that sets a cookie is almost always specific to one user,
+    /// so by default it gets `no-store` ([`Downgrade::NoStore`]) rather than
+    /// the layer's policy.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, Downgrade};
+    ///
+    /// // Leave cookie-setting responses to the handler.
+    /// let layer = CacheControlLayer::new().with_set_cookie_downgrade(Downgrade::Skip);
+    /// ```
+    pub fn with_set_cookie_downgrade(mut self, downgrade: Downgrade) -> Self {
+        self.set_cookie = downgrade;
+        self
+    }
+
+    /// Set how responses to requests with an `Authorization` header are
+    /// treated.
+    ///
+    /// Authenticated responses usually contain per-user content, which must
+    /// not end up in shared caches. By default, such requests aren't treated
+    /// differently ([`Downgrade::Ignore`]).
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, Downgrade};
+    ///
+    /// // Cache-Control: private, max-age=31536000
+    /// let layer = CacheControlLayer::new().with_authorization_downgrade(Downgrade::Private);
+    /// ```
+    pub fn with_authorization_downgrade(mut self, downgrade: Downgrade) -> Self {
+        self.authorization = downgrade;
+        self
+    }
+
+    /// Set how matched `4xx` and `5xx` responses are treated.
+    ///
+    /// An error page that happens to match a long-lived policy, such as a
+    /// `404` for a missing asset, would otherwise be cached as if it were
+    /// the asset, so by default errors get `no-store`
+    /// ([`Downgrade::NoStore`]). This doesn't apply to policies picked by a
+    /// handler, resolver, or policy function, or by a rule with a status
+    /// condition, which are assumed to be meant for the response.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, Downgrade};
+    ///
+    /// // Cache errors like any other response.
+    /// let layer = CacheControlLayer::new().with_error_downgrade(Downgrade::Ignore);
+    /// ```
+    pub fn with_error_downgrade(mut self, downgrade: Downgrade) -> Self {
+        self.error = downgrade;
+        self
+    }
+
+    /// Set how matched responses marked `Content-Disposition: attachment`
+    /// are treated.
+    ///
+    /// Generated downloads, such as exports and invoices, are usually
+    /// specific to the user even when their MIME type is in the layer's list.
+    /// By default they get the layer's policy like any other response
+    /// ([`Downgrade::Ignore`]).
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, Downgrade};
+    ///
+    /// let layer = CacheControlLayer::new().with_attachment_downgrade(Downgrade::NoStore);
+    /// ```
+    ///
+    /// To give attachments a policy of their own instead, add a rule with
+    /// [`Matcher::attachment`].
+    pub fn with_attachment_downgrade(mut self, downgrade: Downgrade) -> Self {
+        self.attachment = downgrade;
+        self
+    }
+
+    /// Add `Accept-Encoding` to the `Vary` header of responses with a
+    /// compressible MIME type (see [`MimeType::is_compressible`]).
+    ///
+    /// Compression middleware only encodes compressible responses, so only
+    /// those need to vary by encoding; images and fonts are left alone
+    /// instead of fragmenting shared caches.
+    pub fn with_smart_vary_encoding(mut self) -> Self {
+        self.smart_vary_encoding = true;
+        self
+    }
+
+    /// Don't add `Accept-Encoding` to the `Vary` header of responses that
+    /// have a `Content-Encoding`.
+    ///
+    /// By default, responses that were compressed before reaching the layer,
+    /// for example by a `CompressionLayer` the `CacheControlLayer` wraps,
+    /// vary by encoding, so that shared caches don't serve compressed bodies
+    /// to clients that can't decode them. Only opt out if every client is
+    /// known to accept the encoding.
+    pub fn without_encoding_vary(mut self) -> Self {
+        self.encoding_vary = false;
+        self
+    }
+
+    /// Also set an `Expires` header, `max-age` seconds from now, for
+    /// HTTP/1.0 clients and older CDNs that ignore `Cache-Control`.
+    ///
+    /// Responses without a `max-age`, such as `no-store` ones, get no
+    /// `Expires`. Modern caches ignore it in favor of `max-age`.
+    pub fn with_expires(mut self) -> Self {
+        self.expires = true;
+        self
+    }
+
+    /// Also set `Pragma: no-cache` on responses marked `no-store` or
+    /// `no-cache`, so HTTP/1.0 intermediaries don't cache them either.
+    pub fn with_pragma(mut self) -> Self {
+        self.pragma = true;
+        self
+    }
+
+    /// Set the strength of the `ETag`s on responses that get `Cache-Control`.
+    ///
+    /// With [`EtagStrength::Weak`], strong tags set by handlers get a `W/`
+    /// prefix, for responses that are transformed further out, e.g. by
+    /// compression middleware. Weak tags can't be made strong without
+    /// knowing the body is identical byte for byte, so
+    /// [`EtagStrength::Strong`], the default, leaves tags as they are.
+    ///
+    /// Tags generated by an [`EtagLayer`](crate::etag::EtagLayer) wrapping
+    /// this layer are set after it runs; give that layer the same strength
+    /// instead, which also decides how it compares `If-None-Match`.
+    ///
+    /// ```rust
+    /// use axum_cc::{etag::EtagStrength, CacheControlLayer};
+    ///
+    /// // ETag: "abc" becomes ETag: W/"abc"
+    /// let layer = CacheControlLayer::new().with_etag_strength(EtagStrength::Weak);
+    /// ```
+    #[cfg(feature = "conditional")]
+    pub fn with_etag_strength(mut self, strength: EtagStrength) -> Self {
+        self.etag_strength = strength;
+        self
+    }
+
+    /// Set an `X-Cache-Control-Rule` header naming where the policy of each
+    /// response came from, to see with `curl` which rule produced it.
+    ///
+    /// The header holds the rule's name for rules added with
+    /// [`CacheControlLayer::named_rule`], `rule-N` for the `N`th other rule
+    /// (counting from zero), or one of `handler`, `resolver`, `policy-fn`,
+    /// `fingerprinted`, `mime-policy`, `mime-types`, `redirect`, and
+    /// `default`. This exposes how the server is configured, so keep it to
+    /// staging environments.
+    pub fn with_rule_header(mut self) -> Self {
+        self.rule_header = true;
+        self
+    }
+
+    /// Compute the headers that would be set, but leave responses untouched.
+    ///
+    /// The computed headers are recorded in a [`DryRun`] response extension
+    /// instead, and with the `tracing` feature enabled, logged at the `INFO`
+    /// level, which makes it possible to observe a new policy against real
+    /// traffic before rolling it out. An observer set with
+    /// [`CacheControlLayer::with_observer`] sees responses as if the layer
+    /// had set them.
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Attach cache tags to every response that has `Cache-Control` set, so
+    /// that a CDN can purge them by tag.
+    ///
+    /// Tags that are empty, contain the header's separator, or contain
+    /// characters that aren't valid in a header are dropped.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlLayer, CacheTagHeader};
+    ///
+    /// // Surrogate-Key: static assets
+    /// let layer = CacheControlLayer::new()
+    ///     .with_cache_tags(CacheTagHeader::SurrogateKey, &["static", "assets"]);
+    /// ```
+    pub fn with_cache_tags(mut self, header: CacheTagHeader, tags: &[&str]) -> Self {
+        self.cache_tags = header.value(tags).map(|value| (header.name(), value));
+        self
+    }
+
+    /// Also emit a targeted cache-control field, such as `CDN-Cache-Control`,
+    /// with its own directives.
+    ///
+    /// The field is set alongside `Cache-Control`, with `directives` emitted
+    /// as-is. It's left out of responses that end up `no-store` or `private`,
+    /// including those downgraded for setting cookies, since the targeted
+    /// cache would otherwise store them anyway. Setting the same field again
+    /// replaces its directives.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlLayer, TargetedField};
+    ///
+    /// // Cache-Control: public, max-age=60
+    /// // CDN-Cache-Control: public, max-age=86400
+    /// let layer = CacheControlLayer::new()
+    ///     .with_max_age(Duration::from_secs(60))
+    ///     .with_targeted(TargetedField::Cdn, Duration::from_secs(60 * 60 * 24));
+    /// ```
+    pub fn with_targeted(
+        mut self,
+        field: TargetedField,
+        directives: impl Into<CacheControlDirectives>,
+    ) -> Self {
+        let name = field.name();
+        self.targeted.retain(|(n, _)| *n != name);
+
+        if let Ok(value) = directives.into().header_value() {
+            self.targeted.push((name, value));
+        }
+
+        self
+    }
+}
+
+impl CacheControlLayer {
+    /// Returns `true` if no response can ever match.
+    fn is_empty(&self) -> bool {
+        self.resolver.is_none()
+            && self.policy_fn.is_none()
+            && self.rules.is_empty()
+            && self.mime_types.is_empty()
+            && self.policies.is_empty()
+            && self.default_policy.is_none()
+            && self.permanent_redirect.is_none()
+            && self.temporary_redirect.is_none()
+    }
+
+    /// Returns `true` if the MIME type or any rule needs the request path.
+    fn uses_path(&self) -> bool {
+        self.mime_from_path
+            || self.fingerprint_paths
+            || self.rules.iter().any(|rule| rule.matcher.uses_path())
+    }
+
+    /// Returns `true` if the layer needs a copy of the request head.
+    fn uses_parts(&self) -> bool {
+        self.policy_fn.is_some()
+            || self.resolver.is_some()
+            || self.rules.iter().any(|rule| rule.surrogate_keys.is_some())
+    }
+
+    /// Find the directives that apply to a response: those picked by the
+    /// handler, the resolver, the policy function, or the first matching rule,
+    /// or else the redirect policy for redirects, or else the MIME type's
+    /// policy or the layer's own directives if the MIME type is matched, or
+    /// else the default policy.
+    fn policy(
+        &self,
+        request: &RequestInfo,
+        response: &response::Parts,
+        cx: &MatchContext<'_>,
+        resolved: Option<CacheControlDirectives>,
+    ) -> Option<Policy<'_>> {
+        if let Some(policy) = response.extensions.get::<CachePolicy>() {
+            return Some(Policy::owned(policy.directives(), Source::Handler));
+        }
+
+        if let Some(directives) = resolved {
+            return Some(Policy::owned(directives, Source::Resolver));
+        }
+
+        if let (Some(PolicyFn(f)), Some(parts)) = (&self.policy_fn, &request.parts) {
+            if let Some(directives) = f(parts, response) {
+                return Some(Policy::owned(directives, Source::PolicyFn));
+            }
+        }
+
+        let precomputed = &self.precomputed;
+
+        if let Some(i) = self.rules.iter().position(|rule| rule.matcher.matches(cx)) {
+            let rule = &self.rules[i];
+
+            return Some(Policy {
+                directives: Cow::Borrowed(&rule.directives),
+                precomputed: precomputed.rules.get(i).and_then(Option::as_ref),
+                rule: Some(rule),
+                source: Source::Rule(i),
+            });
+        }
+
+        let redirect = match response.status {
+            StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT => {
+                Some((&self.permanent_redirect, &precomputed.permanent_redirect))
+            }
+            StatusCode::FOUND | StatusCode::SEE_OTHER | StatusCode::TEMPORARY_REDIRECT => {
+                Some((&self.temporary_redirect, &precomputed.temporary_redirect))
+            }
+            _ => None,
+        };
+
+        if let Some((Some(directives), precomputed)) = redirect {
+            return Some(Policy {
+                directives: Cow::Borrowed(directives),
+                precomputed: precomputed.as_ref(),
+                rule: None,
+                source: Source::Redirect,
+            });
+        }
+
+        self.mime_policy(request, cx).or_else(|| {
+            self.default_policy.as_ref().map(|directives| Policy {
+                directives: Cow::Borrowed(directives),
+                precomputed: precomputed.default_policy.as_ref(),
+                rule: None,
+                source: Source::Default,
+            })
+        })
+    }
+
+    /// Find the directives for a response's MIME type, if the layer matches
+    /// it.
+    fn mime_policy(&self, request: &RequestInfo, cx: &MatchContext<'_>) -> Option<Policy<'_>> {
+        let precomputed = &self.precomputed;
+        let mime = cx.mime?;
+
+        if let Some(directives) = &self.fingerprinted {
+            let matched = self.policies.iter().any(|(m, _)| m == mime)
+                || precomputed.mime_types.contains(mime);
+
+            let fingerprinted =
+                self.fingerprint_paths && cx.path.is_some_and(path::is_fingerprinted);
+
+            if matched && (fingerprinted || request.versioned) {
+                return Some(Policy {
+                    directives: Cow::Borrowed(directives),
+                    precomputed: precomputed.fingerprinted.as_ref(),
+                    rule: None,
+                    source: Source::Fingerprinted,
+                });
+            }
+        }
+
+        if let Some(i) = self.policies.iter().position(|(m, _)| m == mime) {
+            return Some(Policy {
+                directives: Cow::Borrowed(&self.policies[i].1),
+                precomputed: precomputed.policies.get(i).and_then(Option::as_ref),
+                rule: None,
+                source: Source::MimePolicy,
+            });
+        }
+
+        precomputed.mime_types.contains(mime).then_some(Policy {
+            directives: Cow::Borrowed(&self.directives),
+            precomputed: precomputed.directives.as_ref(),
+            rule: None,
+            source: Source::MimeTypes,
+        })
+    }
+
+    /// Precompute the MIME type lookup and the values of policies that don't
+    /// depend on the response.
+    pub(crate) fn precompute(mut self) -> Self {
+        self.precomputed = Precomputed {
+            mime_types: self.mime_types.iter().cloned().collect(),
+            ..Precomputed::default()
+        };
+
+        // These adjust `max-age` per response.
+        if !self.encoding_rules.is_empty()
+            || !self.mtime_buckets.is_empty()
+            || self.age_decay
+            || self.max_age_jitter.is_some()
+            || self.expiry.is_some()
+        {
+            return self;
+        }
+
+        let headers = HeaderMap::new();
+        let value = |directives| self.adjust(directives, &headers, None).header_value().ok();
+
+        let directives = value(&self.directives);
+        let fingerprinted = self.fingerprinted.as_ref().and_then(value);
+        let default_policy = self.default_policy.as_ref().and_then(value);
+        let permanent_redirect = self.permanent_redirect.as_ref().and_then(value);
+        let temporary_redirect = self.temporary_redirect.as_ref().and_then(value);
+        let policies = self.policies.iter().map(|(_, d)| value(d)).collect();
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| value(&rule.directives))
+            .collect();
+
+        self.precomputed.directives = directives;
+        self.precomputed.fingerprinted = fingerprinted;
+        self.precomputed.default_policy = default_policy;
+        self.precomputed.permanent_redirect = permanent_redirect;
+        self.precomputed.temporary_redirect = temporary_redirect;
+        self.precomputed.policies = policies;
+        self.precomputed.rules = rules;
+
+        self
+    }
+
+    /// Adjust a policy's directives for a response with the given headers and
+    /// age in seconds.
+    fn adjust(
+        &self,
+        directives: &CacheControlDirectives,
+        headers: &HeaderMap,
+        age: Option<u64>,
+    ) -> CacheControlDirectives {
+        let mut directives = directives.clone();
+
+        // Only adjust the lifetime of age-based policies; a `no-store` policy
+        // must never gain a `max-age`.
+        let max_age = directives.max_age().map(|max_age| {
+            self.encoded_max_age(headers)
+                .or_else(|| self.bucketed_max_age(headers))
+                .or_else(|| {
+                    self.expiry
+                        .and_then(|expiry| expiry.remaining(headers, SystemTime::now()))
+                })
+                .unwrap_or(max_age)
+        });
+
+        let max_age = match age.filter(|_| self.age_decay) {
+            Some(age) => max_age.map(|max_age| max_age.saturating_sub(Duration::from_secs(age))),
+            None => max_age,
+        };
+
+        let max_age = match self.max_age_jitter {
+            Some(jitter) => max_age.map(|max_age| max_age.saturating_sub(random_up_to(jitter))),
+            None => max_age,
+        };
+
+        directives.set_max_age(max_age.map(|max_age| self.clamp("max-age", max_age)));
+        directives.set_s_maxage(
+            directives
+                .s_maxage()
+                .map(|s_maxage| self.clamp("s-maxage", s_maxage)),
+        );
+
+        directives
+    }
+
+    /// Clamp a directive's number of seconds to the configured ceiling.
+    fn clamp(&self, _directive: &str, value: Duration) -> Duration {
+        match self.max_age_ceiling {
+            Some(ceiling) if value > ceiling => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    directive = _directive,
+                    configured = value.as_secs(),
+                    ceiling = ceiling.as_secs(),
+                    "clamping directive to the max-age ceiling"
+                );
+
+                ceiling
+            }
+            _ => value,
+        }
+    }
+
+    /// Select a `max-age` from the encoding rules based on the response's
+    /// `Content-Encoding`.
+    fn encoded_max_age(&self, headers: &HeaderMap) -> Option<Duration> {
+        if self.encoding_rules.is_empty() {
+            return None;
+        }
+
+        let encoding = headers.get(CONTENT_ENCODING)?.to_str().ok()?.trim();
+
+        self.encoding_rules
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(encoding))
+            .map(|(_, max_age)| *max_age)
+    }
+
+    /// Select a `max-age` from the mtime buckets based on how long ago the
+    /// response's `Last-Modified` date was.
+    fn bucketed_max_age(&self, headers: &HeaderMap) -> Option<Duration> {
+        if self.mtime_buckets.is_empty() {
+            return None;
+        }
+
+        let last_modified = headers.get(LAST_MODIFIED)?.to_str().ok()?;
+        let last_modified = httpdate::parse_http_date(last_modified).ok()?;
+
+        // A modification date in the future means the file is as fresh as it
+        // gets.
+        let age = SystemTime::now()
+            .duration_since(last_modified)
+            .unwrap_or_default();
+
+        self.mtime_buckets
+            .iter()
+            .find(|(within, _)| age < *within)
+            .map(|(_, max_age)| *max_age)
+    }
+
+    /// The value of the rule header for a policy from `source`.
+    fn source_name(&self, source: Source) -> HeaderValue {
+        match source {
+            Source::Rule(i) => match &self.rules[i].name {
+                Some(name) => name.clone(),
+                // Always a valid header value.
+                None => HeaderValue::try_from(format!("rule-{i}"))
+                    .unwrap_or_else(|_| HeaderValue::from_static("rule")),
+            },
+            Source::Handler => HeaderValue::from_static("handler"),
+            Source::Resolver => HeaderValue::from_static("resolver"),
+            Source::PolicyFn => HeaderValue::from_static("policy-fn"),
+            Source::Fingerprinted => HeaderValue::from_static("fingerprinted"),
+            Source::MimePolicy => HeaderValue::from_static("mime-policy"),
+            Source::MimeTypes => HeaderValue::from_static("mime-types"),
+            Source::Redirect => HeaderValue::from_static("redirect"),
+            Source::Default => HeaderValue::from_static("default"),
+        }
+    }
+
+    /// Report why the layer left a response untouched, returning `false`.
+    fn skipped(&self, reason: SkipReason) -> bool {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(reason = reason.as_str(), "not setting Cache-Control");
+
+        #[cfg(feature = "metrics")]
+        metrics::counter!("cache_control_skipped_total", "reason" => reason.as_str()).increment(1);
+
+        if let Some(Observer(observe)) = &self.observer {
+            observe(&Outcome::Skipped(reason));
+        }
+
+        false
+    }
+
+    /// Returns `false` if the layer must leave the response untouched,
+    /// regardless of its policy.
+    fn applies(&self, request: &RequestInfo, response: &response::Parts) -> bool {
+        // A layer with no resolver, policy function, rules, MIME types, or
+        // policies can never match, so don't bother inspecting the response
+        // at all, unless the handler picked a policy itself.
+        if self.is_empty() && response.extensions.is_empty() {
+            return false;
+        }
+
+        if self
+            .methods
+            .as_ref()
+            .is_some_and(|methods| !methods.contains(&request.method))
+        {
+            return self.skipped(SkipReason::Method);
+        }
+
+        // Informational responses (e.g. `101 Switching Protocols` for
+        // WebSocket upgrades) are never cacheable.
+        if response.status.is_informational() {
+            return self.skipped(SkipReason::Informational);
+        }
+
+        if self.range_policy == RangePolicy::SkipPartial
+            && response.status == StatusCode::PARTIAL_CONTENT
+        {
+            return self.skipped(SkipReason::PartialContent);
+        }
+
+        if let Some(marker) = &self.marker_header {
+            if !response.headers.contains_key(marker) {
+                return self.skipped(SkipReason::MissingMarker);
+            }
+        }
+
+        if self
+            .skip_if_headers
+            .iter()
+            .any(|name| response.headers.contains_key(name))
+        {
+            return self.skipped(SkipReason::SkipHeader);
+        }
+
+        // A handler policy sets the header itself, but still goes through the
+        // layer, so that its guards apply.
+        if self.overwrite == OverwriteBehavior::SkipIfPresent
+            && response.headers.contains_key(CACHE_CONTROL)
+            && response.extensions.get::<CachePolicy>().is_none()
+        {
+            return self.skipped(SkipReason::ExistingHeader);
+        }
+
+        true
+    }
+
+    /// Set the headers for a response to the given request, unless the layer
+    /// must leave it untouched.
+    fn apply(&self, request: &RequestInfo, response: &mut response::Parts) {
+        if self.applies(request, response) {
+            self.set_headers(request, response, None);
+        }
+    }
+
+    /// Set the headers for a response that [`CacheControlLayer::applies`]
+    /// to, using `resolved` as its policy if the resolver picked one.
+    fn set_headers(
+        &self,
+        request: &RequestInfo,
+        response: &mut response::Parts,
+        resolved: Option<CacheControlDirectives>,
+    ) {
+        // Unknown and malformed types match nothing.
+        let mime = match response.headers.get(CONTENT_TYPE) {
+            Some(value) => MimeType::try_from(value).ok(),
+            None if self.mime_from_path => request.path.as_deref().and_then(MimeType::from_path),
+            None => None,
+        };
+
+        let cx = MatchContext {
+            method: &request.method,
+            path: request.path.as_deref(),
+            mime: mime.as_ref(),
+            status: response.status,
+            content_length: MatchContext::content_length(&response.headers),
+            attachment: MatchContext::attachment(&response.headers),
+        };
+
+        let Some(Policy {
+            directives: policy,
+            precomputed,
+            rule: matched,
+            source,
+        }) = self.policy(request, response, &cx, resolved)
+        else {
+            self.skipped(match cx.mime {
+                Some(_) => SkipReason::UnmatchedMime,
+                None if response.headers.contains_key(CONTENT_TYPE) => {
+                    SkipReason::UnknownContentType
+                }
+                None => SkipReason::NoContentType,
+            });
+            return;
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?source, content_type = ?mime, "matched a Cache-Control policy");
+
+        // An existing `Age` comes from a cache closer to the origin, which
+        // knows better than a `StoredAt` extension.
+        let (age, age_header) = match response.headers.get(AGE) {
+            Some(value) => (
+                value.to_str().ok().and_then(|age| age.trim().parse().ok()),
+                None,
+            ),
+            None => {
+                let age = response
+                    .extensions
+                    .get::<StoredAt>()
+                    .filter(|_| self.age)
+                    .map(|StoredAt(stored)| {
+                        SystemTime::now()
+                            .duration_since(*stored)
+                            .unwrap_or_default()
+                            .as_secs()
+                    });
+
+                (age, age.map(HeaderValue::from))
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        if let Cow::Owned(directives) = &policy {
+            if let Err(error) = directives.validate() {
+                tracing::warn!(%error, %directives, "emitting invalid Cache-Control directives");
+            }
+        }
+
+        let mut downgrade = Downgrade::Ignore;
+
+        if response.headers.contains_key(SET_COOKIE) {
+            downgrade = downgrade.max(self.set_cookie);
+        }
+
+        if cx.attachment {
+            downgrade = downgrade.max(self.attachment);
+        }
+
+        if request.authorized {
+            downgrade = downgrade.max(self.authorization);
+        }
+
+        let error = response.status.is_client_error() || response.status.is_server_error();
+
+        let implicit = match source {
+            Source::Handler | Source::Resolver | Source::PolicyFn => false,
+            Source::Rule(i) => !self.rules[i].matcher.uses_status(),
+            Source::Fingerprinted
+            | Source::MimePolicy
+            | Source::MimeTypes
+            | Source::Redirect
+            | Source::Default => true,
+        };
+
+        if error && implicit {
+            downgrade = downgrade.max(self.error);
+        }
+
+        let rule = request.fetch_site.and_then(|site| {
+            self.fetch_site_rules
+                .iter()
+                .find(|(s, _)| *s == site)
+                .map(|(_, directives)| directives)
+        });
+
+        #[cfg(feature = "tracing")]
+        if downgrade != Downgrade::Ignore {
+            tracing::debug!(
+                ?downgrade,
+                set_cookie = response.headers.contains_key(SET_COOKIE),
+                attachment = cx.attachment,
+                authorization = request.authorized,
+                status = response.status.as_u16(),
+                "downgrading the policy"
+            );
+        } else if rule.is_some() {
+            tracing::debug!(fetch_site = ?request.fetch_site, "overriding the policy for Sec-Fetch-Site");
+        }
+
+        let value = match (downgrade, rule) {
+            (Downgrade::Skip, _) => {
+                self.skipped(SkipReason::Downgrade);
+                return;
+            }
+            (Downgrade::NoStore, _) => HeaderValue::from_static("no-store"),
+            (Downgrade::Private, _) => {
+                let mut directives = self
+                    .adjust(&policy, &response.headers, age)
+                    .with_visibility(Visibility::Private);
+                // `s-maxage` only applies to shared caches.
+                directives.set_s_maxage(None);
+
+                let Ok(value) = directives.header_value() else {
+                    self.skipped(SkipReason::InvalidHeader);
+                    return;
+                };
+
+                value
+            }
+            (Downgrade::Ignore, Some(value)) => value.clone(),
+            (Downgrade::Ignore, None) => match precomputed {
+                Some(value) => value.clone(),
+                None => match self.adjust(&policy, &response.headers, age).header_value() {
+                    Ok(value) => value,
+                    Err(_) => {
+                        self.skipped(SkipReason::InvalidHeader);
+                        return;
+                    }
+                },
+            },
+        };
+
+        #[cfg(feature = "tracing")]
+        if let Some(existing) = response.headers.get(CACHE_CONTROL) {
+            tracing::debug!(?existing, overwrite = ?self.overwrite, "replacing existing Cache-Control");
+        }
+
+        let value = match response.headers.get(CACHE_CONTROL) {
+            Some(existing) if self.overwrite == OverwriteBehavior::Merge => {
+                match (existing.to_str(), value.to_str()) {
+                    (Ok(existing), Ok(value)) => {
+                        match HeaderValue::from_str(&merge::merge(existing, value)) {
+                            Ok(value) => value,
+                            Err(_) => {
+                                self.skipped(SkipReason::InvalidHeader);
+                                return;
+                            }
+                        }
+                    }
+                    // We can't merge into a header we can't read, so leave the
+                    // response as the handler intended.
+                    _ => {
+                        self.skipped(SkipReason::InvalidHeader);
+                        return;
+                    }
+                }
+            }
+            _ => value,
+        };
+
+        let expires = if self.expires {
+            value
+                .to_str()
+                .ok()
+                .and_then(merge::max_age)
+                .and_then(|max_age| SystemTime::now().checked_add(Duration::from_secs(max_age)))
+                .and_then(|expires| HeaderValue::from_str(&httpdate::fmt_http_date(expires)).ok())
+        } else {
+            None
+        };
+
+        let pragma = self.pragma
+            && value.to_str().is_ok_and(|value| {
+                merge::contains(value, "no-store") || merge::contains(value, "no-cache")
+            });
+
+        #[cfg(feature = "conditional")]
+        let etag = match self.etag_strength {
+            EtagStrength::Weak => etag::weaken(&response.headers),
+            EtagStrength::Strong => None,
+        };
+
+        // A targeted cache would obey its own field over `Cache-Control`.
+        let targeted = if downgrade == Downgrade::Ignore
+            && value.to_str().is_ok_and(|value| {
+                !merge::contains(value, "no-store") && !merge::contains(value, "private")
+            }) {
+            self.targeted.as_slice()
+        } else {
+            &[]
+        };
+
+        let mut tags: Vec<_> = self.cache_tags.iter().cloned().collect();
+
+        let keys = matched
+            .and_then(|rule| rule.surrogate_keys.as_ref())
+            .zip(request.parts.as_ref())
+            .and_then(|(KeyFn(keys), parts)| {
+                CacheTagHeader::SurrogateKey.value(&keys(parts, response))
+            });
+
+        if let Some(keys) = keys {
+            let name = CacheTagHeader::SurrogateKey.name();
+
+            match tags.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, existing)) => {
+                    let joined = [existing.as_bytes(), b" ", keys.as_bytes()].concat();
+
+                    if let Ok(value) = HeaderValue::from_bytes(&joined) {
+                        *existing = value;
+                    }
+                }
+                None => tags.push((name, keys)),
+            }
+        }
+
+        let mut vary_names = Vec::new();
+
+        if self.content_negotiation_vary {
+            vary_names.push(ACCEPT);
+        }
+
+        if !self.encoding_rules.is_empty()
+            || (self.smart_vary_encoding && mime.as_ref().is_some_and(MimeType::is_compressible))
+            || (self.encoding_vary && is_encoded(&response.headers))
+        {
+            vary_names.push(ACCEPT_ENCODING);
+        }
+
+        vary_names.extend(self.vary.iter().cloned());
+
+        if let Some(rule) = matched {
+            vary_names.extend(rule.vary.iter().cloned());
+        }
+
+        if let (Source::MimePolicy, Some(mime)) = (source, &mime) {
+            if let Some((_, names)) = self.policy_vary.iter().find(|(m, _)| m == mime) {
+                vary_names.extend(names.iter().cloned());
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::counter!(
+            "cache_control_applied_total",
+            "mime" => mime.as_ref().map_or("none", MimeType::as_str).to_owned(),
+            "rule" => self.source_name(source).to_str().unwrap_or("rule").to_owned(),
+        )
+        .increment(1);
+
+        if let Some(Observer(observe)) = &self.observer {
+            observe(&Outcome::Applied {
+                mime: mime.as_ref(),
+                rule: match source {
+                    Source::Rule(i) => Some(i),
+                    _ => None,
+                },
+            });
+        }
+
+        #[cfg(feature = "tracing")]
+        if self.dry_run {
+            tracing::info!(
+                cache_control = ?value,
+                expires = ?expires,
+                age = ?age_header,
+                pragma,
+                targeted = ?targeted,
+                cache_tags = ?tags,
+                vary = ?vary_names,
+                content_type = ?mime,
+                "dry run: would set Cache-Control"
+            );
+        } else {
+            tracing::debug!(?source, cache_control = ?value, "setting Cache-Control");
+        }
+
+        let targeted: Vec<_> = targeted
+            .iter()
+            .filter(|(name, _)| {
+                self.overwrite == OverwriteBehavior::Always || !response.headers.contains_key(name)
+            })
+            .collect();
+
+        // In dry-run mode, the headers are recorded in an extension instead.
+        let mut recorded = HeaderMap::new();
+        let headers = if self.dry_run {
+            &mut recorded
+        } else {
+            &mut response.headers
+        };
+
+        headers.insert(CACHE_CONTROL, value);
+
+        if let Some(expires) = expires {
+            headers.insert(EXPIRES, expires);
+        }
+
+        if pragma {
+            headers.insert(PRAGMA, HeaderValue::from_static("no-cache"));
+        }
+
+        #[cfg(feature = "conditional")]
+        if let Some(etag) = etag {
+            headers.insert(ETAG, etag);
+        }
+
+        if let Some(age) = age_header {
+            headers.insert(AGE, age);
+        }
+
+        if self.rule_header {
+            headers.insert(CACHE_CONTROL_RULE.clone(), self.source_name(source));
+        }
+
+        for (name, value) in targeted {
+            headers.insert(name, value.clone());
+        }
+
+        for (name, tags) in tags {
+            headers.insert(name, tags);
+        }
+
+        for name in &vary_names {
+            vary::append(headers, name);
+        }
+
+        if self.dry_run {
+            response.extensions.insert(DryRun(recorded));
+        }
+    }
+}
+
+/// The directives picked for a response.
+struct Policy<'a> {
+    directives: Cow<'a, CacheControlDirectives>,
+    /// The serialized directives, if they don't depend on the response.
+    precomputed: Option<&'a HeaderValue>,
+    /// The rule the directives came from, if any.
+    rule: Option<&'a Rule>,
+    source: Source,
+}
+
+impl Policy<'_> {
+    fn owned(directives: CacheControlDirectives, source: Source) -> Self {
+        Self {
+            directives: Cow::Owned(directives),
+            precomputed: None,
+            rule: None,
+            source,
+        }
+    }
+}
+
+/// Where the directives for a response came from.
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    /// A [`CachePolicy`] set by the handler.
+    Handler,
+    Resolver,
+    PolicyFn,
+    /// The rule at this index.
+    Rule(usize),
+    /// The directives for fingerprinted and versioned URLs.
+    Fingerprinted,
+    /// The policy for the response's MIME type.
+    MimePolicy,
+    /// The layer's own directives, for one of its MIME types.
+    MimeTypes,
+    /// The policy for redirects.
+    Redirect,
+    /// The policy for responses nothing else matched.
+    Default,
+}
+
+/// `public, max-age=31536000, immutable`, for URLs whose content never
+/// changes.
+fn immutable_year() -> CacheControlDirectives {
+    CacheControlDirectives::new()
+        .public()
+        .with_max_age(Duration::from_secs(31_536_000))
+        .immutable()
+}
+
+/// Returns `true` if `query` has one of `params` with a non-empty value.
+fn has_version_param(query: &str, params: &[String]) -> bool {
+    query.split('&').any(|pair| match pair.split_once('=') {
+        Some((name, value)) => !value.is_empty() && params.iter().any(|param| param == name),
+        None => false,
+    })
+}
+
+/// Pick a random whole number of seconds between zero and `max`, inclusive.
+///
+/// This is not a real random number generator: the values come from hashing
+/// nothing with a freshly seeded `RandomState`, and the modulo slightly favors
+/// small ones. That's fine for spreading out expiry, but nothing that needs
+/// unpredictable or uniform numbers should use it.
+fn random_up_to(max: Duration) -> Duration {
+    // Every `RandomState` is seeded differently, which is all the randomness
+    // jitter needs.
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_secs(random % (max.as_secs().saturating_add(1)))
+}
+
+/// Returns `true` if a response has a `Content-Encoding` other than
+/// `identity`.
+fn is_encoded(headers: &HeaderMap) -> bool {
+    headers.get_all(CONTENT_ENCODING).iter().any(|value| {
+        !value
+            .as_bytes()
+            .trim_ascii()
+            .eq_ignore_ascii_case(b"identity")
+    })
+}
+
+#[cfg(feature = "serde")]
+impl From<CacheControlConfig> for CacheControlLayer {
+    fn from(config: CacheControlConfig) -> Self {
+        Self::from_config(config)
+    }
+}
+
+impl<S> Layer<S> for CacheControlLayer {
+    type Service = CacheControl<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CacheControl {
+            inner,
+            layer: Arc::new(self.clone().precompute()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheControl<S> {
+    inner: S,
+    layer: Arc<CacheControlLayer>,
+}
+
+impl<S, T, U> Service<Request<T>> for CacheControl<S>
+where
+    S: Service<Request<T>, Response = Response<U>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        ResponseFuture::new(self.layer.clone(), &mut self.inner, req)
+    }
+}
+
+/// The parts of a request the layer needs once the response is ready.
+#[derive(Debug)]
+struct RequestInfo {
+    method: Method,
+    /// Whether the request carried an `Authorization` header.
+    authorized: bool,
+    /// The normalized request path, only captured if the layer needs it.
+    path: Option<String>,
+    fetch_site: Option<FetchSite>,
+    /// Whether the request carried one of the layer's version parameters.
+    versioned: bool,
+    /// The request head, only captured if there is a policy function or
+    /// resolver.
+    parts: Option<request::Parts>,
+}
+
+pin_project! {
+    pub struct ResponseFuture<F>
+    where
+        F: Future,
+    {
+        #[pin]
+        response_future: F,
+        layer: Arc<CacheControlLayer>,
+        request: RequestInfo,
+        // The response, held back while the resolver runs.
+        pending: Option<(F::Output, ResolveFuture)>,
+    }
+}
+
+impl<F: Future> ResponseFuture<F> {
+    /// Call `inner` with `req`, capturing what `layer` needs to know about
+    /// the request once the response is ready.
+    pub(crate) fn new<S, T>(layer: Arc<CacheControlLayer>, inner: &mut S, req: Request<T>) -> Self
+    where
+        S: Service<Request<T>, Future = F>,
+    {
+        let fetch_site = if layer.fetch_site_rules.is_empty() {
+            None
+        } else {
+            req.headers()
+                .get(SEC_FETCH_SITE)
+                .and_then(FetchSite::from_header)
+        };
+
+        let path = layer.uses_path().then(|| path::normalize(req.uri().path()));
+
+        let versioned = !layer.version_params.is_empty()
+            && req
+                .uri()
+                .query()
+                .is_some_and(|query| has_version_param(query, &layer.version_params));
+
+        let (req, parts) = if layer.uses_parts() {
+            let (parts, body) = req.into_parts();
+            (Request::from_parts(parts.clone(), body), Some(parts))
+        } else {
+            (req, None)
+        };
+
+        let method = req.method().clone();
+        let authorized = req.headers().contains_key(AUTHORIZATION);
+        let response_future = inner.call(req);
+
+        ResponseFuture {
+            response_future,
+            layer,
+            request: RequestInfo {
+                method,
+                authorized,
+                path,
+                fetch_site,
+                versioned,
+                parts,
+            },
+            pending: None,
+        }
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            if let Some((_, resolving)) = this.pending {
+                let resolved = ready!(resolving.as_mut().poll(cx));
+
+                let Some((Ok(response), _)) = this.pending.take() else {
+                    unreachable!("only successful responses are held back");
+                };
+
+                let (mut parts, body) = response.into_parts();
+                this.layer.set_headers(this.request, &mut parts, resolved);
+
+                return Poll::Ready(Ok(Response::from_parts(parts, body)));
+            }
+
+            let response: Response<B> = ready!(this.response_future.as_mut().poll(cx))?;
+            let (mut parts, body) = response.into_parts();
+
+            if let (Some(Resolver(resolver)), Some(request)) =
+                (&this.layer.resolver, &this.request.parts)
+            {
+                if !this.layer.applies(this.request, &parts) {
+                    return Poll::Ready(Ok(Response::from_parts(parts, body)));
+                }
+
+                if parts.extensions.get::<CachePolicy>().is_none() {
+                    let resolving = resolver.resolve(request, &parts);
+                    *this.pending = Some((Ok(Response::from_parts(parts, body)), resolving));
+                    continue;
+                }
+
+                this.layer.set_headers(this.request, &mut parts, None);
+            } else {
+                this.layer.apply(this.request, &mut parts);
+            }
+
+            return Poll::Ready(Ok(Response::from_parts(parts, body)));
+        }
+    }
+}
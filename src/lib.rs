@@ -1,183 +1,110 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(any(feature = "conditional", feature = "sniff"))]
+mod body;
+#[cfg(feature = "conditional")]
+mod cache_status;
+#[cfg(feature = "std")]
+pub mod clear_site_data;
+#[cfg(feature = "serde")]
+pub mod config;
+pub mod directives;
+mod duration;
+mod error;
+#[cfg(feature = "conditional")]
+pub mod etag;
+pub mod fetch_site;
+#[cfg(feature = "conditional")]
+pub mod last_modified;
+#[cfg(feature = "std")]
+mod layer;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "axum")]
+mod middleware;
 pub mod mime;
-
-use std::{
-    fmt,
-    future::Future,
-    pin::Pin,
-    task::{Context, Poll},
-    time::Duration,
+#[cfg(feature = "std")]
+mod outcome;
+#[cfg(feature = "std")]
+mod path;
+#[cfg(feature = "std")]
+mod policy;
+#[cfg(feature = "std")]
+mod resolver;
+#[cfg(feature = "std")]
+mod response;
+#[cfg(feature = "axum")]
+mod router;
+#[cfg(feature = "std")]
+mod rule;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "sniff")]
+pub mod sniff;
+#[cfg(feature = "std")]
+pub mod tags;
+#[cfg(feature = "std")]
+mod targeted;
+#[cfg(feature = "test_util")]
+pub mod test_util;
+#[cfg(feature = "std")]
+mod vary;
+
+#[cfg(any(feature = "conditional", feature = "sniff"))]
+pub use crate::body::BufferedBody;
+pub use crate::{
+    directives::{CacheControlDirectives, Visibility},
+    duration::parse_duration,
+    error::CacheControlError,
+    fetch_site::FetchSite,
+    mime::MimeType,
 };
-
-use axum_core::response::Response;
-use futures_core::ready;
-use http::{
-    header::{CACHE_CONTROL, CONTENT_TYPE},
-    HeaderValue, Request,
+#[cfg(feature = "std")]
+pub use crate::{
+    layer::{
+        CacheControl, CacheControlLayer, Downgrade, Expiry, OverwriteBehavior, RangePolicy,
+        ResponseFuture,
+    },
+    outcome::{Outcome, SkipReason},
+    policy::CachePolicy,
+    resolver::{CachePolicyResolver, ResolveFuture},
+    response::{Cached, DryRun, NoCache, StoredAt},
+    rule::Matcher,
+    shared::{SharedCacheControl, SharedCacheControlLayer},
+    tags::CacheTagHeader,
+    targeted::TargetedField,
 };
-use pin_project_lite::pin_project;
-use tower_layer::Layer;
-use tower_service::Service;
-
-pub use crate::mime::MimeType;
-
-const DEFAULT_MIME_TYPES: [MimeType; 6] = [
-    MimeType::CSS,
-    MimeType::JS,
-    MimeType::SVG,
-    MimeType::WEBP,
-    MimeType::WOFF2,
-    MimeType::PNG,
-];
+#[cfg(feature = "axum")]
+pub use crate::{middleware::cache_control, router::RouterCacheControlExt};
 
-/// A [`tower::Layer`] that sets `Cache-Control` headers on responses.
+/// Set `Cache-Control` on the responses of a single handler.
 ///
-/// See
-/// <https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cache-Control>
-/// for more information.
-#[derive(Debug, Default, Clone, Copy)]
-pub struct CacheControlLayer<'a> {
-    mime_types: &'a [MimeType],
-    max_age: Duration,
-    // TODO: add support for remaining directives
-}
-
-impl<'a> CacheControlLayer<'a> {
-    /// Create a new `CacheControlLayer` with the default configuration.
-    ///
-    /// The default configuration sets `Cache-Control` headers for the following
-    /// MIME types:
-    ///
-    /// - `text/css`
-    /// - `application/javascript`
-    /// - `image/svg+xml`
-    /// - `image/webp`
-    /// - `font/woff2`
-    ///
-    /// The `max-age` value is set to 1 year.
-    ///
-    /// If you wish to set your own MIME types and/or `max-age` value, use
-    /// [`CacheControlLayer::with_mime_types`] and/or
-    /// [`CacheControlLayer::with_max_age`].
-    ///
-    /// As these are builder methods, you can chain them together:
-    ///
-    /// ```rust
-    /// use std::time::Duration;
-    /// use axum_cc::{CacheControlLayer, MimeType};
-    ///
-    /// let layer = CacheControlLayer::default() // empty headers
-    ///    .with_mime_types(&[MimeType::CSS])
-    ///    .with_max_age(Duration::from_secs(86400));
-    /// ```
-    pub fn new() -> Self {
-        Self {
-            mime_types: &DEFAULT_MIME_TYPES,
-            max_age: Duration::from_secs(60 * 60 * 24 * 365),
-        }
-    }
-
-    /// Set the MIME types that should have `Cache-Control` headers set.
-    pub fn with_mime_types(mut self, mime_types: &'a [MimeType]) -> Self {
-        self.mime_types = mime_types;
-        self
-    }
-
-    /// Set the `max-age` value for the `Cache-Control` header.
-    pub fn with_max_age(mut self, max_age: impl Into<Duration>) -> Self {
-        self.max_age = max_age.into();
-        self
-    }
-}
-
-impl<'a, S> Layer<S> for CacheControlLayer<'a> {
-    type Service = CacheControl<'a, S>;
-
-    fn layer(&self, inner: S) -> Self::Service {
-        CacheControl {
-            inner,
-            layer: *self,
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct CacheControl<'a, S> {
-    inner: S,
-    layer: CacheControlLayer<'a>,
-}
-
-impl<'a, S, T, U> Service<Request<T>> for CacheControl<'a, S>
-where
-    S: Service<Request<T>, Response = Response<U>>,
-    U: Default,
-{
-    type Response = S::Response;
-    type Error = S::Error;
-    type Future = ResponseFuture<'a, S::Future>;
-
-    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.inner.poll_ready(cx)
-    }
-
-    fn call(&mut self, req: Request<T>) -> Self::Future {
-        let response_future = self.inner.call(req);
-
-        ResponseFuture {
-            response_future,
-            layer: self.layer,
-        }
-    }
-}
-
-pin_project! {
-    pub struct ResponseFuture<'a, F> {
-        #[pin]
-        response_future: F,
-        layer: CacheControlLayer<'a>,
-    }
-}
-
-impl<'a, F, B, E> Future for ResponseFuture<'a, F>
-where
-    F: Future<Output = Result<Response<B>, E>>,
-    B: Default,
-{
-    type Output = Result<Response<B>, E>;
-
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let this = self.project();
-        let mut response: Response<B> = ready!(this.response_future.poll(cx))?;
-
-        if let Some(content_type) = response.headers().get(CONTENT_TYPE) {
-            let mime = MimeType::from(content_type);
-
-            if this.layer.mime_types.contains(&mime) {
-                let value = format!("public, max-age={}", this.layer.max_age.as_secs());
-
-                if let Ok(value) = HeaderValue::from_str(&value) {
-                    response.headers_mut().insert(CACHE_CONTROL, value);
-                }
-            }
-        }
-
-        Poll::Ready(Ok(response))
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CacheControlError {
-    InvalidMaxAge,
-    InvalidMimeType,
-}
-
-impl fmt::Display for CacheControlError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            CacheControlError::InvalidMaxAge => write!(f, "invalid max-age value"),
-            CacheControlError::InvalidMimeType => write!(f, "invalid MIME type"),
-        }
-    }
-}
-
-impl std::error::Error for CacheControlError {}
+/// The attribute takes the same directives as [`CacheControlDirectives`],
+/// written in snake_case: the flags `public`, `private`, `no_cache`,
+/// `no_store`, `must_revalidate`, `proxy_revalidate`, `no_transform`, and
+/// `immutable`, and the lifetimes `max_age`, `s_maxage`,
+/// `stale_while_revalidate`, and `stale_if_error`. Lifetimes are a number of
+/// seconds or a string accepted by [`parse_duration`].
+///
+/// ```rust
+/// use axum::{routing::get, Router};
+/// use axum_cc::cache_control;
+///
+/// #[cache_control(public, max_age = "1d", immutable)]
+/// async fn logo() -> &'static str {
+///     "<svg></svg>"
+/// }
+///
+/// let app: Router = Router::new().route("/logo.svg", get(logo));
+/// ```
+///
+/// The handler's response is wrapped in [`Cached`], so the handler now
+/// returns `Cached<T>` instead of `T`, and a `CacheControlLayer` further out
+/// keeps its directives. Conflicting directives, as rejected by
+/// [`CacheControlDirectives::validate`], are a compile error.
+#[cfg(feature = "macros")]
+pub use axum_cc_macros::cache_control;
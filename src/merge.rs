@@ -0,0 +1,163 @@
+//! Parsing and merging of serialized `Cache-Control` directives.
+
+/// A single `Cache-Control` directive, such as `public` or `max-age=60`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Directive<'a> {
+    pub name: &'a str,
+    pub value: Option<&'a str>,
+}
+
+impl<'a> Directive<'a> {
+    fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+
+    fn write(&self, out: &mut String) {
+        if !out.is_empty() {
+            out.push_str(", ");
+        }
+
+        out.push_str(self.name);
+
+        if let Some(value) = self.value {
+            out.push('=');
+            out.push_str(value);
+        }
+    }
+}
+
+/// Parse a `Cache-Control` header value into its directives.
+///
+/// Commas inside quoted values (e.g. `private="set-cookie, x-user"`) do not
+/// split directives. Empty list elements are ignored.
+pub(crate) fn parse(value: &str) -> Vec<Directive<'_>> {
+    let mut directives = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                push_directive(&mut directives, &value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    push_directive(&mut directives, &value[start..]);
+
+    directives
+}
+
+fn push_directive<'a>(directives: &mut Vec<Directive<'a>>, element: &'a str) {
+    let element = element.trim();
+
+    if element.is_empty() {
+        return;
+    }
+
+    let directive = match element.split_once('=') {
+        Some((name, value)) => Directive {
+            name: name.trim(),
+            value: Some(value.trim()),
+        },
+        None => Directive {
+            name: element,
+            value: None,
+        },
+    };
+
+    directives.push(directive);
+}
+
+/// Merge the layer's directives into an existing `Cache-Control` value.
+///
+/// When both set the same directive, the layer's value wins. The exception is
+/// restrictions set by the existing header: `private` and `no-cache` are
+/// always kept, and `no-store` leaves the existing value untouched.
+///
+/// Directives that would contradict a restriction on either side are dropped
+/// rather than emitted alongside it: `public` and `s-maxage` don't survive
+/// `private`, `immutable` doesn't survive `no-cache`, and nothing that lets
+/// caches store the response survives the layer's `no-store`.
+pub(crate) fn merge(existing: &str, layer: &str) -> String {
+    let existing_value = existing;
+    let existing = parse(existing);
+    let layer = parse(layer);
+
+    if existing.iter().any(|d| d.is("no-store")) {
+        return existing_value.to_owned();
+    }
+
+    let mut merged: Vec<Directive<'_>> = existing
+        .iter()
+        .filter(|directive| {
+            ["private", "no-cache"]
+                .iter()
+                .any(|name| directive.is(name))
+                || !layer.iter().any(|d| d.is(directive.name))
+        })
+        .copied()
+        .collect();
+
+    for directive in layer {
+        if !merged.iter().any(|d| d.is(directive.name)) {
+            merged.push(directive);
+        }
+    }
+
+    let has = |name| merged.iter().any(|d: &Directive<'_>| d.is(name));
+    let no_store = has("no-store");
+    let private = has("private");
+    let no_cache = has("no-cache");
+
+    merged.retain(|directive| {
+        let conflicts = if no_store {
+            STORED.iter().any(|name| directive.is(name))
+        } else {
+            (private && (directive.is("public") || directive.is("s-maxage")))
+                || (no_cache && directive.is("immutable"))
+        };
+
+        !conflicts
+    });
+
+    let mut value = String::new();
+
+    for directive in &merged {
+        directive.write(&mut value);
+    }
+
+    value
+}
+
+/// Directives that let caches store a response, which `no-store` forbids.
+const STORED: [&str; 6] = [
+    "public",
+    "max-age",
+    "s-maxage",
+    "stale-while-revalidate",
+    "stale-if-error",
+    "immutable",
+];
+
+/// Find the `max-age` of a `Cache-Control` value, in seconds.
+pub(crate) fn max_age(value: &str) -> Option<u64> {
+    parse(value)
+        .iter()
+        .find(|d| d.is("max-age"))?
+        .value?
+        .trim_matches('"')
+        .parse()
+        .ok()
+}
+
+/// Returns `true` if a `Cache-Control` value contains the named directive.
+pub(crate) fn contains(value: &str, name: &str) -> bool {
+    parse(value).iter().any(|d| d.is(name))
+}
@@ -0,0 +1,35 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{layer::ResponseFuture, SharedCacheControlLayer};
+
+/// A middleware function that sets `Cache-Control` like a
+/// [`CacheControlLayer`](crate::CacheControlLayer), for use with
+/// [`axum::middleware::from_fn_with_state`].
+///
+/// The state is a [`SharedCacheControlLayer`], so the policy can also be
+/// reloaded while the server is running.
+///
+/// ```rust
+/// use axum::{middleware, routing::get, Router};
+/// use axum_cc::{cache_control, CacheControlLayer, SharedCacheControlLayer};
+///
+/// let policy = SharedCacheControlLayer::new(CacheControlLayer::static_assets());
+///
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "hello" }))
+///     .layer(middleware::from_fn_with_state(policy, cache_control));
+/// ```
+pub async fn cache_control(
+    State(layer): State<SharedCacheControlLayer>,
+    req: Request,
+    mut next: Next,
+) -> Response {
+    let response = ResponseFuture::new(layer.current(), &mut next, req).await;
+    response.unwrap_or_else(|never: Infallible| match never {})
+}
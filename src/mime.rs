@@ -1,9 +1,14 @@
-use std::fmt::Display;
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+use core::{fmt::Display, str::FromStr};
 
+#[cfg(feature = "std")]
 use http::HeaderValue;
 
+use crate::error::CacheControlError;
+
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MimeType {
     CSS,
     HTML,
@@ -13,19 +18,202 @@ pub enum MimeType {
     WEBP,
     WOFF2,
     PNG,
+    PDF,
+    CSV,
+    JSON,
+    XML,
+    WASM,
+    JPEG,
+    GIF,
+    AVIF,
+    ICO,
+    TTF,
+    OTF,
+    EOT,
+    MP4,
+    WEBM,
+    MP3,
+    MANIFEST,
+    /// Any other MIME type, such as `application/vnd.mycorp.report+json`.
+    ///
+    /// The type should be lowercase and without parameters, which
+    /// [`MimeType::custom`] takes care of.
+    #[cfg(feature = "alloc")]
+    Custom(Cow<'static, str>),
 }
 
 impl MimeType {
+    /// Create a [`MimeType::Custom`] for a type that isn't enumerated.
+    ///
+    /// ```rust
+    /// use axum_cc::MimeType;
+    ///
+    /// let report = MimeType::custom("application/vnd.mycorp.report+json");
+    /// assert_eq!(report.as_str(), "application/vnd.mycorp.report+json");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn custom(mime: impl Into<Cow<'static, str>>) -> Self {
+        let mime = mime.into();
+        let essence = mime.split(';').next().unwrap_or_default().trim();
+
+        if essence.len() == mime.len() && !essence.bytes().any(|b| b.is_ascii_uppercase()) {
+            return MimeType::Custom(mime);
+        }
+
+        MimeType::Custom(Cow::Owned(essence.to_ascii_lowercase()))
+    }
+
+    /// Guess the MIME type for a file extension, defaulting to `text/plain`.
+    ///
+    /// With the `mime_guess` feature, extensions that aren't enumerated are
+    /// looked up in its database and may return a [`MimeType::Custom`].
     pub fn from_extension(ext: &str) -> Self {
+        Self::guess_extension(ext).unwrap_or(MimeType::TEXT)
+    }
+
+    /// Guess the MIME type of a file from its path's extension.
+    ///
+    /// Only the last extension counts, so `app.min.js` is JavaScript.
+    /// Extensions are case-insensitive, and anything after a `?` or `#` is
+    /// ignored, so URL paths work too. Returns `None` for paths without a
+    /// known extension, consulting the `mime_guess` database first if that
+    /// feature is enabled.
+    ///
+    /// ```rust
+    /// use axum_cc::MimeType;
+    ///
+    /// assert_eq!(MimeType::from_path("assets/Logo.PNG"), Some(MimeType::PNG));
+    /// assert_eq!(MimeType::from_path("/app.min.js?v=3"), Some(MimeType::JS));
+    /// assert_eq!(MimeType::from_path("notes.draft"), None);
+    /// assert_eq!(MimeType::from_path("Makefile"), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        let path = path.as_ref().to_str()?;
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let name = path.rsplit(['/', '\\']).next().unwrap_or_default();
+        let (stem, ext) = name.rsplit_once('.')?;
+
+        // Dotfiles like `.env` have no extension.
+        if stem.is_empty() {
+            return None;
+        }
+
+        Self::guess_extension(&ext.to_ascii_lowercase())
+    }
+
+    fn guess_extension(ext: &str) -> Option<Self> {
+        let known = Self::known_extension(ext);
+
+        #[cfg(feature = "mime_guess")]
+        let known = known.or_else(|| {
+            let guess = mime_guess::from_ext(ext).first()?;
+            guess.essence_str().parse().ok()
+        });
+
+        known
+    }
+
+    /// Guess the MIME type of a body from its first bytes, by magic numbers
+    /// for binary formats and by the leading markup for HTML, SVG, and XML.
+    ///
+    /// A few hundred bytes are enough for every recognized format. Returns
+    /// `None` if nothing matches.
+    ///
+    /// ```rust
+    /// use axum_cc::MimeType;
+    ///
+    /// assert_eq!(MimeType::sniff(b"\x89PNG\r\n\x1a\n..."), Some(MimeType::PNG));
+    /// assert_eq!(MimeType::sniff(b"  <svg xmlns=\"http://www.w3.org/2000/svg\">"), Some(MimeType::SVG));
+    /// assert_eq!(MimeType::sniff(b"hello"), None);
+    /// ```
+    #[cfg(feature = "sniff")]
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        const SIGNATURES: [(&[u8], MimeType); 12] = [
+            (b"\x89PNG\r\n\x1a\n", MimeType::PNG),
+            (b"\xff\xd8\xff", MimeType::JPEG),
+            (b"GIF87a", MimeType::GIF),
+            (b"GIF89a", MimeType::GIF),
+            (b"wOF2", MimeType::WOFF2),
+            (b"OTTO", MimeType::OTF),
+            (b"\x00\x01\x00\x00", MimeType::TTF),
+            (b"\x00\x00\x01\x00", MimeType::ICO),
+            (b"%PDF-", MimeType::PDF),
+            (b"\x00asm", MimeType::WASM),
+            (b"\x1a\x45\xdf\xa3", MimeType::WEBM),
+            (b"ID3", MimeType::MP3),
+        ];
+
+        if let Some((_, mime)) = SIGNATURES
+            .iter()
+            .find(|(signature, _)| bytes.starts_with(signature))
+        {
+            return Some(mime.clone());
+        }
+
+        if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+            return Some(MimeType::WEBP);
+        }
+
+        if bytes.get(4..8) == Some(b"ftyp") {
+            return match bytes.get(8..12) {
+                Some(b"avif" | b"avis") => Some(MimeType::AVIF),
+                Some(b"isom" | b"iso2" | b"mp41" | b"mp42" | b"avc1" | b"dash") => {
+                    Some(MimeType::MP4)
+                }
+                _ => None,
+            };
+        }
+
+        let text = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+        let start = text.iter().position(|b| !b.is_ascii_whitespace())?;
+        let text = &text[start..];
+
+        let starts_with = |prefix: &[u8]| {
+            text.get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+        };
+
+        if starts_with(b"<!doctype html") || starts_with(b"<html") {
+            Some(MimeType::HTML)
+        } else if starts_with(b"<svg") {
+            Some(MimeType::SVG)
+        } else if starts_with(b"<?xml") {
+            // An XML declaration often precedes an SVG's root element.
+            let svg = text.windows(4).any(|window| window == b"<svg");
+            Some(if svg { MimeType::SVG } else { MimeType::XML })
+        } else {
+            None
+        }
+    }
+
+    fn known_extension(ext: &str) -> Option<Self> {
         match ext {
-            "css" => MimeType::CSS,
-            "html" => MimeType::HTML,
-            "js" => MimeType::JS,
-            "svg" => MimeType::SVG,
-            "webp" => MimeType::WEBP,
-            "woff2" => MimeType::WOFF2,
-            "png" => MimeType::PNG,
-            _ => MimeType::TEXT,
+            "css" => Some(MimeType::CSS),
+            "html" | "htm" => Some(MimeType::HTML),
+            "js" | "mjs" => Some(MimeType::JS),
+            "svg" => Some(MimeType::SVG),
+            "txt" => Some(MimeType::TEXT),
+            "webp" => Some(MimeType::WEBP),
+            "woff2" => Some(MimeType::WOFF2),
+            "png" => Some(MimeType::PNG),
+            "pdf" => Some(MimeType::PDF),
+            "csv" => Some(MimeType::CSV),
+            "json" => Some(MimeType::JSON),
+            "xml" => Some(MimeType::XML),
+            "wasm" => Some(MimeType::WASM),
+            "jpg" | "jpeg" => Some(MimeType::JPEG),
+            "gif" => Some(MimeType::GIF),
+            "avif" => Some(MimeType::AVIF),
+            "ico" => Some(MimeType::ICO),
+            "ttf" => Some(MimeType::TTF),
+            "otf" => Some(MimeType::OTF),
+            "eot" => Some(MimeType::EOT),
+            "mp4" => Some(MimeType::MP4),
+            "webm" => Some(MimeType::WEBM),
+            "mp3" => Some(MimeType::MP3),
+            "webmanifest" => Some(MimeType::MANIFEST),
+            _ => None,
         }
     }
 
@@ -39,35 +227,293 @@ impl MimeType {
             MimeType::WEBP => "image/webp",
             MimeType::WOFF2 => "font/woff2",
             MimeType::PNG => "image/png",
+            MimeType::PDF => "application/pdf",
+            MimeType::CSV => "text/csv",
+            MimeType::JSON => "application/json",
+            MimeType::XML => "application/xml",
+            MimeType::WASM => "application/wasm",
+            MimeType::JPEG => "image/jpeg",
+            MimeType::GIF => "image/gif",
+            MimeType::AVIF => "image/avif",
+            MimeType::ICO => "image/x-icon",
+            MimeType::TTF => "font/ttf",
+            MimeType::OTF => "font/otf",
+            MimeType::EOT => "application/vnd.ms-fontobject",
+            MimeType::MP4 => "video/mp4",
+            MimeType::WEBM => "video/webm",
+            MimeType::MP3 => "audio/mpeg",
+            MimeType::MANIFEST => "application/manifest+json",
+            #[cfg(feature = "alloc")]
+            MimeType::Custom(mime) => mime,
+        }
+    }
+
+    /// The top-level type, such as `image` for `image/png`.
+    pub fn top_level(&self) -> &str {
+        let mime = self.as_str();
+        mime.split_once('/')
+            .map_or(mime, |(top_level, _)| top_level)
+    }
+
+    /// Returns `true` if this type belongs to the given family, written as
+    /// either a top-level type (`image`) or a wildcard (`image/*`). A full
+    /// type matches only itself.
+    ///
+    /// ```rust
+    /// use axum_cc::MimeType;
+    ///
+    /// assert!(MimeType::AVIF.matches("image/*"));
+    /// assert!(MimeType::TTF.matches("font"));
+    /// assert!(MimeType::CSS.matches("text/css"));
+    /// assert!(!MimeType::CSS.matches("image/*"));
+    /// ```
+    pub fn matches(&self, pattern: &str) -> bool {
+        let family = pattern.strip_suffix("/*").unwrap_or(pattern);
+
+        if family.contains('/') {
+            return self.as_str().eq_ignore_ascii_case(family);
+        }
+
+        self.top_level().eq_ignore_ascii_case(family)
+    }
+
+    /// Returns `true` if responses of this type benefit from compression.
+    ///
+    /// Text-based and uncompressed binary formats are compressible, while
+    /// formats that are already compressed (most images, WOFF2 fonts, audio,
+    /// and video) are not.
+    pub fn is_compressible(&self) -> bool {
+        match self {
+            MimeType::CSS
+            | MimeType::CSV
+            | MimeType::EOT
+            | MimeType::HTML
+            | MimeType::ICO
+            | MimeType::JS
+            | MimeType::JSON
+            | MimeType::MANIFEST
+            | MimeType::OTF
+            | MimeType::SVG
+            | MimeType::TEXT
+            | MimeType::TTF
+            | MimeType::WASM
+            | MimeType::XML => true,
+            MimeType::AVIF
+            | MimeType::GIF
+            | MimeType::JPEG
+            | MimeType::MP3
+            | MimeType::MP4
+            | MimeType::PDF
+            | MimeType::PNG
+            | MimeType::WEBM
+            | MimeType::WEBP
+            | MimeType::WOFF2 => false,
+            #[cfg(feature = "alloc")]
+            MimeType::Custom(mime) => {
+                mime.starts_with("text/") || mime.ends_with("+json") || mime.ends_with("+xml")
+            }
         }
     }
 }
 
-impl From<&HeaderValue> for MimeType {
-    fn from(header: &HeaderValue) -> Self {
-        let header = header
-            .to_str()
-            .unwrap_or_default()
-            .split(';')
-            .next()
-            .unwrap_or_default();
-
-        match header {
-            "text/css" => MimeType::CSS,
-            "text/html" => MimeType::HTML,
-            "application/javascript" => MimeType::JS,
-            "image/svg+xml" => MimeType::SVG,
-            "text/plain" => MimeType::TEXT,
-            "image/webp" => MimeType::WEBP,
-            "font/woff2" => MimeType::WOFF2,
-            "image/png" => MimeType::PNG,
-            _ => MimeType::TEXT,
+/// A set of MIME types with constant-time lookups for the enumerated types.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MimeSet {
+    bits: u64,
+    custom: Vec<MimeType>,
+}
+
+#[cfg(feature = "std")]
+impl MimeSet {
+    pub(crate) fn contains(&self, mime: &MimeType) -> bool {
+        match Self::bit(mime) {
+            Some(bit) => self.bits & bit != 0,
+            None => self.custom.contains(mime),
         }
     }
+
+    fn insert(&mut self, mime: MimeType) {
+        match Self::bit(&mime) {
+            Some(bit) => self.bits |= bit,
+            None => self.custom.push(mime),
+        }
+    }
+
+    fn bit(mime: &MimeType) -> Option<u64> {
+        let index = match mime {
+            MimeType::CSS => 0,
+            MimeType::HTML => 1,
+            MimeType::JS => 2,
+            MimeType::SVG => 3,
+            MimeType::TEXT => 4,
+            MimeType::WEBP => 5,
+            MimeType::WOFF2 => 6,
+            MimeType::PNG => 7,
+            MimeType::PDF => 8,
+            MimeType::CSV => 9,
+            MimeType::JSON => 10,
+            MimeType::XML => 11,
+            MimeType::WASM => 12,
+            MimeType::JPEG => 13,
+            MimeType::GIF => 14,
+            MimeType::AVIF => 15,
+            MimeType::ICO => 16,
+            MimeType::TTF => 17,
+            MimeType::OTF => 18,
+            MimeType::EOT => 19,
+            MimeType::MP4 => 20,
+            MimeType::WEBM => 21,
+            MimeType::MP3 => 22,
+            MimeType::MANIFEST => 23,
+            MimeType::Custom(_) => return None,
+        };
+
+        Some(1 << index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromIterator<MimeType> for MimeSet {
+    fn from_iter<I: IntoIterator<Item = MimeType>>(iter: I) -> Self {
+        let mut set = Self::default();
+
+        for mime in iter {
+            set.insert(mime);
+        }
+
+        set
+    }
+}
+
+/// The essences (`type/subtype`) of the enumerated MIME types, including
+/// aliases.
+const ESSENCES: [(&str, MimeType); 27] = [
+    ("text/css", MimeType::CSS),
+    ("text/html", MimeType::HTML),
+    ("application/javascript", MimeType::JS),
+    ("text/javascript", MimeType::JS),
+    ("image/svg+xml", MimeType::SVG),
+    ("text/plain", MimeType::TEXT),
+    ("image/webp", MimeType::WEBP),
+    ("font/woff2", MimeType::WOFF2),
+    ("image/png", MimeType::PNG),
+    ("application/pdf", MimeType::PDF),
+    ("text/csv", MimeType::CSV),
+    ("application/json", MimeType::JSON),
+    ("application/xml", MimeType::XML),
+    ("text/xml", MimeType::XML),
+    ("application/wasm", MimeType::WASM),
+    ("image/jpeg", MimeType::JPEG),
+    ("image/gif", MimeType::GIF),
+    ("image/avif", MimeType::AVIF),
+    ("image/x-icon", MimeType::ICO),
+    ("image/vnd.microsoft.icon", MimeType::ICO),
+    ("font/ttf", MimeType::TTF),
+    ("font/otf", MimeType::OTF),
+    ("application/vnd.ms-fontobject", MimeType::EOT),
+    ("video/mp4", MimeType::MP4),
+    ("video/webm", MimeType::WEBM),
+    ("audio/mpeg", MimeType::MP3),
+    ("application/manifest+json", MimeType::MANIFEST),
+];
+
+/// Returns `true` if `s` is a valid token, as defined in RFC 9110.
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+impl FromStr for MimeType {
+    type Err = CacheControlError;
+
+    /// Parse a MIME type, ignoring any parameters (like `; charset=utf-8`).
+    ///
+    /// Types that aren't enumerated parse as [`MimeType::Custom`], or fail
+    /// without the `alloc` feature.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let essence = s.split(';').next().unwrap_or_default().trim();
+
+        let valid = essence
+            .split_once('/')
+            .is_some_and(|(top_level, subtype)| is_token(top_level) && is_token(subtype));
+
+        if !valid {
+            return Err(CacheControlError::InvalidMimeType);
+        }
+
+        if let Some((_, mime)) = ESSENCES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(essence))
+        {
+            return Ok(mime.clone());
+        }
+
+        #[cfg(feature = "alloc")]
+        return Ok(MimeType::custom(alloc::string::String::from(essence)));
+
+        #[cfg(not(feature = "alloc"))]
+        Err(CacheControlError::InvalidMimeType)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<&HeaderValue> for MimeType {
+    type Error = CacheControlError;
+
+    fn try_from(header: &HeaderValue) -> Result<Self, Self::Error> {
+        // Most responses carry one of a handful of exact, parameter-free
+        // values, so check for those before validating and splitting the
+        // header.
+        match header.as_bytes() {
+            b"text/css" => return Ok(MimeType::CSS),
+            b"application/javascript" | b"text/javascript" => return Ok(MimeType::JS),
+            b"image/png" => return Ok(MimeType::PNG),
+            b"image/svg+xml" => return Ok(MimeType::SVG),
+            b"image/webp" => return Ok(MimeType::WEBP),
+            b"font/woff2" => return Ok(MimeType::WOFF2),
+            b"application/json" => return Ok(MimeType::JSON),
+            b"image/jpeg" => return Ok(MimeType::JPEG),
+            _ => {}
+        }
+
+        header
+            .to_str()
+            .map_err(|_| CacheControlError::InvalidMimeType)?
+            .parse()
+    }
+}
+
+#[cfg(feature = "mime")]
+impl From<&::mime::Mime> for MimeType {
+    fn from(mime: &::mime::Mime) -> Self {
+        mime.essence_str()
+            .parse()
+            .unwrap_or_else(|_| MimeType::custom(mime.essence_str().to_owned()))
+    }
+}
+
+#[cfg(feature = "mime")]
+impl From<::mime::Mime> for MimeType {
+    fn from(mime: ::mime::Mime) -> Self {
+        MimeType::from(&mime)
+    }
+}
+
+#[cfg(feature = "mime")]
+impl From<MimeType> for ::mime::Mime {
+    /// Convert to a [`mime::Mime`](::mime::Mime). Custom types that aren't
+    /// valid MIME types become `application/octet-stream`.
+    fn from(mime: MimeType) -> Self {
+        mime.as_str()
+            .parse()
+            .unwrap_or(::mime::APPLICATION_OCTET_STREAM)
+    }
 }
 
 impl Display for MimeType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_str(self.as_str())
     }
 }
@@ -0,0 +1,70 @@
+use crate::mime::MimeType;
+
+/// What a `CacheControlLayer` did with a response, as reported to the
+/// function passed to
+/// [`CacheControlLayer::with_observer`](crate::CacheControlLayer::with_observer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome<'a> {
+    /// The layer set `Cache-Control`, or would have in dry-run mode.
+    Applied {
+        /// The response's MIME type, if it had a known one.
+        mime: Option<&'a MimeType>,
+        /// The index of the rule the policy came from, if any.
+        rule: Option<usize>,
+    },
+    /// The layer left the response untouched.
+    Skipped(SkipReason),
+}
+
+/// Why a `CacheControlLayer` left a response untouched.
+///
+/// New reasons may be added as the layer learns new checks, so matches on
+/// this enum need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// The layer doesn't handle the request's method.
+    Method,
+    /// The response has an informational (`1xx`) status.
+    Informational,
+    /// The response is a `206 Partial Content`, and the range policy skips
+    /// those.
+    PartialContent,
+    /// The response lacks the layer's marker header.
+    MissingMarker,
+    /// The response carries one of the layer's skip headers.
+    SkipHeader,
+    /// The response already has `Cache-Control`, and the layer keeps it.
+    ExistingHeader,
+    /// The response has no `Content-Type`.
+    NoContentType,
+    /// The response's `Content-Type` couldn't be parsed.
+    UnknownContentType,
+    /// No rule or policy matched the response's MIME type.
+    UnmatchedMime,
+    /// A `Set-Cookie`, `Authorization`, error status, or
+    /// `Content-Disposition: attachment` downgrade skips the response.
+    Downgrade,
+    /// The directives, or the existing header to merge them into, couldn't
+    /// be written as a header value.
+    InvalidHeader,
+}
+
+impl SkipReason {
+    /// A short snake_case name for the reason, suitable as a metrics label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Method => "method",
+            SkipReason::Informational => "informational",
+            SkipReason::PartialContent => "partial_content",
+            SkipReason::MissingMarker => "missing_marker",
+            SkipReason::SkipHeader => "skip_header",
+            SkipReason::ExistingHeader => "existing_header",
+            SkipReason::NoContentType => "no_content_type",
+            SkipReason::UnknownContentType => "unknown_content_type",
+            SkipReason::UnmatchedMime => "unmatched_mime",
+            SkipReason::Downgrade => "downgrade",
+            SkipReason::InvalidHeader => "invalid_header",
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Request path normalization for path-based matching.
+
+/// Normalize a request path before matching it against rules.
+///
+/// Percent-encoded bytes are decoded, repeated slashes are collapsed, and `.`
+/// and `..` segments are resolved. `..` never escapes the root, so
+/// `/static/../../admin` and `/static/%2e%2e/admin` both become `/admin`
+/// rather than matching a `/static` rule. Encoded slashes (`%2F`) stay
+/// encoded, so they can't split a segment or add a `..` of their own. A
+/// trailing slash is preserved.
+pub(crate) fn normalize(path: &str) -> String {
+    let decoded = percent_decode(path.as_bytes());
+    let decoded = String::from_utf8_lossy(&decoded);
+
+    let mut segments: Vec<&str> = Vec::new();
+
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let mut normalized = String::with_capacity(decoded.len());
+
+    for segment in &segments {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+
+    let trailing_slash =
+        decoded.ends_with('/') || decoded.ends_with("/.") || decoded.ends_with("/..");
+
+    if normalized.is_empty() || trailing_slash {
+        normalized.push('/');
+    }
+
+    normalized
+}
+
+/// Returns `true` if `path` is `prefix` or lies beneath it. Both must already
+/// be normalized.
+///
+/// Matching is segment-aware: `/static` matches `/static/app.js`, but not
+/// `/statically.js`.
+pub(crate) fn has_prefix(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+/// Returns `true` if the file name at the end of `path` contains a content
+/// hash, like `app.3fa9c2.js` or `style-5d41402a.css`.
+///
+/// A hash is a part of the name, delimited by `.`, `-`, or `_`, of at least
+/// six hexadecimal digits, including at least one decimal digit and one
+/// letter, so that IDs and dates like `12345678` or `20240101` don't count.
+/// The extension itself is never a hash.
+pub(crate) fn is_fingerprinted(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or_default();
+
+    let Some((stem, _extension)) = name.rsplit_once('.') else {
+        return false;
+    };
+
+    stem.split(['.', '-', '_']).any(|part| {
+        let hex = part.len() >= 6 && part.bytes().all(|b| b.is_ascii_hexdigit());
+        let digit = part.bytes().any(|b| b.is_ascii_digit());
+        let letter = part.bytes().any(|b| b.is_ascii_alphabetic());
+
+        hex && digit && letter
+    })
+}
+
+/// Decode `%XX` escapes, leaving malformed escapes as they are.
+///
+/// `%2F` is kept (as uppercase), since decoding it would turn part of a
+/// segment into a separator.
+fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < input.len() {
+        if input[i] == b'%' && i + 2 < input.len() {
+            if let (Some(high), Some(low)) = (hex(input[i + 1]), hex(input[i + 2])) {
+                match high << 4 | low {
+                    b'/' => output.extend_from_slice(b"%2F"),
+                    byte => output.push(byte),
+                }
+                i += 3;
+                continue;
+            }
+        }
+
+        output.push(input[i]);
+        i += 1;
+    }
+
+    output
+}
+
+fn hex(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// A compiled path glob, such as `/assets/**/*.js`.
+///
+/// `*` matches any run of characters within a segment, `?` matches a single
+/// character, and a `**` segment matches any number of segments (including
+/// none).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Glob {
+    segments: Vec<String>,
+}
+
+impl Glob {
+    pub(crate) fn new(pattern: &str) -> Self {
+        let segments = pattern
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        Self { segments }
+    }
+
+    /// Returns `true` if the (normalized) path matches the glob.
+    pub(crate) fn matches(&self, path: &str) -> bool {
+        let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match_segments(&self.segments, &path)
+    }
+}
+
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => match_segment(first, segment) && match_segments(rest, path),
+            None => false,
+        },
+    }
+}
+
+/// Match a single segment against a pattern containing `*` and `?`.
+fn match_segment(pattern: &str, segment: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let segment: Vec<char> = segment.chars().collect();
+
+    let (mut p, mut s) = (0, 0);
+    // Where to resume after the most recent `*`, as (pattern, segment).
+    let mut star = None;
+
+    while s < segment.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, s));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                s += 1;
+            }
+            Some(c) if *c == segment[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => match star {
+                // Let the last `*` swallow one more character and retry.
+                Some((star_p, star_s)) => {
+                    p = star_p;
+                    s = star_s + 1;
+                    star = Some((star_p, star_s + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_resolves_dot_segments() {
+        assert_eq!(normalize("/static/./app.js"), "/static/app.js");
+        assert_eq!(normalize("/static/../admin"), "/admin");
+        assert_eq!(normalize("/x/../static/y"), "/static/y");
+        assert_eq!(normalize("/static/.."), "/");
+        assert_eq!(normalize("/static/."), "/static/");
+    }
+
+    #[test]
+    fn normalize_never_escapes_the_root() {
+        assert_eq!(normalize("/.."), "/");
+        assert_eq!(normalize("/static/../../admin"), "/admin");
+        assert_eq!(normalize("../../etc/passwd"), "/etc/passwd");
+    }
+
+    #[test]
+    fn normalize_collapses_slashes() {
+        assert_eq!(normalize("//static//app.js"), "/static/app.js");
+        assert_eq!(normalize("/static///"), "/static/");
+        assert_eq!(normalize(""), "/");
+    }
+
+    #[test]
+    fn normalize_decodes_percent_escapes() {
+        assert_eq!(normalize("/static/%2e%2e/admin"), "/admin");
+        assert_eq!(normalize("/static/%2E%2E/admin"), "/admin");
+        assert_eq!(normalize("/%73tatic/app.js"), "/static/app.js");
+        assert_eq!(normalize("/caf%C3%A9"), "/café");
+    }
+
+    #[test]
+    fn normalize_keeps_encoded_slashes_within_their_segment() {
+        assert_eq!(normalize("/static%2fapp.js"), "/static%2Fapp.js");
+        assert_eq!(
+            normalize("/api/x%2f..%2f..%2fstatic/y"),
+            "/api/x%2F..%2F..%2Fstatic/y"
+        );
+    }
+
+    #[test]
+    fn normalize_keeps_malformed_escapes() {
+        assert_eq!(normalize("/100%"), "/100%");
+        assert_eq!(normalize("/a%2"), "/a%2");
+        assert_eq!(normalize("/a%zz"), "/a%zz");
+    }
+
+    #[test]
+    fn has_prefix_is_segment_aware() {
+        assert!(has_prefix("/static", "/static"));
+        assert!(has_prefix("/static/app.js", "/static"));
+        assert!(has_prefix("/static/app.js", "/static/"));
+        assert!(has_prefix("/anything", "/"));
+        assert!(!has_prefix("/statically.js", "/static"));
+        assert!(!has_prefix("/admin", "/static"));
+    }
+
+    #[test]
+    fn glob_star_matches_within_a_segment() {
+        let glob = Glob::new("/assets/*.js");
+        assert!(glob.matches("/assets/app.js"));
+        assert!(glob.matches("/assets/.js"));
+        assert!(!glob.matches("/assets/app.css"));
+        assert!(!glob.matches("/assets/js/app.js"));
+
+        let glob = Glob::new("/assets/app.*.js");
+        assert!(glob.matches("/assets/app.3fa9c2.js"));
+        assert!(!glob.matches("/assets/app.js"));
+    }
+
+    #[test]
+    fn glob_question_mark_matches_one_character() {
+        let glob = Glob::new("/v?/users");
+        assert!(glob.matches("/v1/users"));
+        assert!(!glob.matches("/v/users"));
+        assert!(!glob.matches("/v10/users"));
+    }
+
+    #[test]
+    fn glob_double_star_matches_any_number_of_segments() {
+        let glob = Glob::new("/assets/**/*.js");
+        assert!(glob.matches("/assets/app.js"));
+        assert!(glob.matches("/assets/js/app.js"));
+        assert!(glob.matches("/assets/js/vendor/lib/app.js"));
+        assert!(!glob.matches("/assets/js/app.css"));
+        assert!(!glob.matches("/static/js/app.js"));
+
+        let glob = Glob::new("/assets/**");
+        assert!(glob.matches("/assets"));
+        assert!(glob.matches("/assets/img/logo.png"));
+        assert!(!glob.matches("/static/logo.png"));
+    }
+
+    #[test]
+    fn glob_ignores_trailing_and_repeated_slashes() {
+        let glob = Glob::new("/docs/*/");
+        assert!(glob.matches("/docs/intro"));
+        assert!(glob.matches("/docs/intro/"));
+        assert!(Glob::new("/docs//intro").matches("/docs/intro"));
+        assert!(!glob.matches("/docs"));
+        assert!(!glob.matches("/docs/intro/setup"));
+    }
+
+    #[test]
+    fn hashed_file_names_are_fingerprinted() {
+        assert!(is_fingerprinted("/assets/app.3fa9c2.js"));
+        assert!(is_fingerprinted("/assets/style-5d41402a.css"));
+        assert!(is_fingerprinted("/assets/chunk_9e107d9d372bb682.js"));
+        assert!(is_fingerprinted("/assets/vendor.8f14e45f.min.js"));
+    }
+
+    #[test]
+    fn plain_file_names_are_not_fingerprinted() {
+        assert!(!is_fingerprinted("/assets/app.js"));
+        assert!(!is_fingerprinted("/users/12345678.json"));
+        assert!(!is_fingerprinted("/reports/report-20240101.pdf"));
+        assert!(!is_fingerprinted("/assets/cafebabe.js"));
+        assert!(!is_fingerprinted("/assets/app.3fa9c.js"));
+        assert!(!is_fingerprinted("/assets/app.abc123"));
+        assert!(!is_fingerprinted("/assets/3fa9c2"));
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use crate::directives::CacheControlDirectives;
+
+/// A per-response override of the layer's policy, inserted into the
+/// response's extensions by a handler.
+///
+/// The layer honors it over its resolver, policy function, rules, and MIME
+/// types, which makes one-off exceptions possible without an extra layer:
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum::response::{IntoResponse, Response};
+/// use axum_cc::CachePolicy;
+///
+/// async fn handler() -> Response {
+///     let mut response = "hello".into_response();
+///     response
+///         .extensions_mut()
+///         .insert(CachePolicy::from(Duration::from_secs(60)));
+///     response
+/// }
+/// ```
+///
+/// The layer's guards still apply, so a response that sets a cookie is
+/// downgraded as configured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Emit the given directives.
+    Directives(CacheControlDirectives),
+    /// Never cache the response, emitting `no-store`.
+    NoCache,
+}
+
+impl CachePolicy {
+    /// The directives to emit for this policy.
+    pub(crate) fn directives(&self) -> CacheControlDirectives {
+        match self {
+            Self::Directives(directives) => directives.clone(),
+            Self::NoCache => CacheControlDirectives::new().no_store(),
+        }
+    }
+}
+
+impl From<CacheControlDirectives> for CachePolicy {
+    fn from(directives: CacheControlDirectives) -> Self {
+        Self::Directives(directives)
+    }
+}
+
+impl From<Duration> for CachePolicy {
+    /// Create a policy emitting `public, max-age=N` directives.
+    fn from(max_age: Duration) -> Self {
+        Self::Directives(max_age.into())
+    }
+}
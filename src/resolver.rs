@@ -0,0 +1,53 @@
+use std::{future::Future, pin::Pin};
+
+use http::{request, response};
+
+use crate::directives::CacheControlDirectives;
+
+/// The future returned by [`CachePolicyResolver::resolve`].
+pub type ResolveFuture = Pin<Box<dyn Future<Output = Option<CacheControlDirectives>> + Send>>;
+
+/// Picks the directives for a response asynchronously, for policies that
+/// depend on external state such as feature flags or TTLs stored in a
+/// database.
+///
+/// The resolver is called once the inner service has produced a response
+/// that the layer would otherwise set headers on, and the response is held
+/// back until the returned future completes. Like
+/// [`CacheControlLayer::with_policy_fn`], it takes precedence over rules and
+/// MIME types, and returning `None` falls back to those. It isn't called for
+/// responses that carry a [`CachePolicy`] from the handler.
+///
+/// The future can't borrow from the request or response, so copy out
+/// whatever it needs before returning it:
+///
+/// ```rust
+/// use std::{collections::HashMap, sync::Arc, time::Duration};
+///
+/// use axum_cc::{CacheControlLayer, CachePolicyResolver, ResolveFuture};
+/// use http::{request, response};
+///
+/// /// TTLs per path, updated by some background task.
+/// struct Ttls(Arc<std::sync::RwLock<HashMap<String, u64>>>);
+///
+/// impl CachePolicyResolver for Ttls {
+///     fn resolve(&self, request: &request::Parts, _: &response::Parts) -> ResolveFuture {
+///         let ttls = self.0.clone();
+///         let path = request.uri.path().to_owned();
+///
+///         Box::pin(async move {
+///             let ttl = *ttls.read().unwrap().get(&path)?;
+///             Some(Duration::from_secs(ttl).into())
+///         })
+///     }
+/// }
+///
+/// let layer = CacheControlLayer::new().with_resolver(Ttls(Default::default()));
+/// ```
+///
+/// [`CacheControlLayer::with_policy_fn`]: crate::CacheControlLayer::with_policy_fn
+/// [`CachePolicy`]: crate::CachePolicy
+pub trait CachePolicyResolver: Send + Sync + 'static {
+    /// Resolve the directives for a response to the given request.
+    fn resolve(&self, request: &request::Parts, response: &response::Parts) -> ResolveFuture;
+}
@@ -0,0 +1,131 @@
+use std::time::SystemTime;
+
+use axum_core::response::{IntoResponse, Response};
+use http::{header::CACHE_CONTROL, HeaderMap};
+
+use crate::{directives::CacheControlDirectives, policy::CachePolicy};
+
+/// Wraps a response, setting its `Cache-Control` header from the handler.
+///
+/// The directives are also stored as a [`CachePolicy`], so a
+/// `CacheControlLayer` further out emits the same directives instead of its
+/// own, even if it's configured to overwrite existing headers. Its guards
+/// still apply: by default, a response that also sets a cookie gets
+/// `no-store`.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use axum_cc::Cached;
+///
+/// async fn handler() -> Cached<&'static str> {
+///     Cached::new(Duration::from_secs(60), "hello")
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cached<T>(pub CacheControlDirectives, pub T);
+
+impl<T> Cached<T> {
+    /// Wrap `inner`, caching it according to `directives`.
+    pub fn new(directives: impl Into<CacheControlDirectives>, inner: T) -> Self {
+        Self(directives.into(), inner)
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Cached<T> {
+    fn into_response(self) -> Response {
+        let Self(directives, inner) = self;
+        let mut response = inner.into_response();
+
+        if let Ok(value) = directives.header_value() {
+            response.headers_mut().insert(CACHE_CONTROL, value);
+        }
+
+        response
+            .extensions_mut()
+            .insert(CachePolicy::Directives(directives));
+        response
+    }
+}
+
+/// Wraps a response, marking it `no-store` from the handler.
+///
+/// Like [`Cached`], this also stores a [`CachePolicy`], so a
+/// `CacheControlLayer` further out never makes the response cacheable.
+///
+/// ```rust
+/// use axum_cc::NoCache;
+///
+/// async fn handler() -> NoCache<String> {
+///     NoCache(String::from("your account balance"))
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NoCache<T>(pub T);
+
+impl<T: IntoResponse> IntoResponse for NoCache<T> {
+    fn into_response(self) -> Response {
+        let mut response = self.0.into_response();
+        let directives = CachePolicy::NoCache.directives();
+
+        if let Ok(value) = directives.header_value() {
+            response.headers_mut().insert(CACHE_CONTROL, value);
+        }
+
+        response.extensions_mut().insert(CachePolicy::NoCache);
+        response
+    }
+}
+
+/// When a response was stored by a server-side cache, inserted into the
+/// response's extensions by a handler that serves it from that cache.
+///
+/// A [`CacheControlLayer`] configured with
+/// [`with_age`](crate::CacheControlLayer::with_age) turns it into an `Age`
+/// header, so that caches further out count the time the response already
+/// spent in the server's cache against its freshness lifetime.
+///
+/// ```rust
+/// use std::time::SystemTime;
+///
+/// use axum::response::{IntoResponse, Response};
+/// use axum_cc::StoredAt;
+///
+/// fn from_cache(body: String, stored: SystemTime) -> Response {
+///     let mut response = body.into_response();
+///     response.extensions_mut().insert(StoredAt(stored));
+///     response
+/// }
+/// ```
+///
+/// [`CacheControlLayer`]: crate::CacheControlLayer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredAt(pub SystemTime);
+
+/// The headers a `CacheControlLayer` in dry-run mode would have set on a
+/// response, stored as a response extension.
+///
+/// Middleware further out can record them, for example to compare them
+/// with what's served today before turning the layer on:
+///
+/// ```rust
+/// use axum::{extract::Request, middleware::Next, response::Response};
+/// use axum_cc::DryRun;
+///
+/// async fn record(req: Request, next: Next) -> Response {
+///     let response = next.run(req).await;
+///
+///     if let Some(DryRun(headers)) = response.extensions().get::<DryRun>() {
+///         println!("would set {headers:?}");
+///     }
+///
+///     response
+/// }
+/// ```
+///
+/// Headers the layer adds to rather than replaces, like `Vary`, hold only
+/// what the layer would add.
+///
+/// [`CacheControlLayer`]: crate::CacheControlLayer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DryRun(pub HeaderMap);
@@ -0,0 +1,97 @@
+use std::convert::Infallible;
+
+use axum::{extract::Request, response::IntoResponse, Router};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{CacheControl, CacheControlLayer};
+
+/// Scope [`CacheControlLayer`]s to parts of an axum [`Router`].
+///
+/// `Router::layer` applies a layer to every route, so giving assets and an
+/// API different policies otherwise means building each subtree as its own
+/// router and layering it before nesting it. These helpers do that in one
+/// call:
+///
+/// ```rust
+/// use axum::{routing::get, Router};
+/// use axum_cc::{CacheControlLayer, RouterCacheControlExt};
+/// use tower_http::services::ServeDir;
+///
+/// let api = Router::new().route("/users", get(|| async { "[]" }));
+///
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "hello" }))
+///     .cache_static("/assets", ServeDir::new("assets"))
+///     .nest_cached("/api", api, CacheControlLayer::api());
+/// ```
+///
+/// The layer only sees requests for the nested subtree, with `path`
+/// stripped from their URI, so path rules of the layer are written relative
+/// to it.
+pub trait RouterCacheControlExt<S> {
+    /// Nest `router` at `path`, with `layer` applied to its routes.
+    fn nest_cached(
+        self,
+        path: &str,
+        router: Router<S>,
+        layer: impl Into<CacheControlLayer>,
+    ) -> Self;
+
+    /// Nest `service` at `path`, with `layer` applied to its responses.
+    fn nest_service_cached<T>(
+        self,
+        path: &str,
+        service: T,
+        layer: impl Into<CacheControlLayer>,
+    ) -> Self
+    where
+        CacheControl<T>: Service<Request, Error = Infallible> + Clone + Send + 'static,
+        <CacheControl<T> as Service<Request>>::Response: IntoResponse,
+        <CacheControl<T> as Service<Request>>::Future: Send + 'static;
+
+    /// Nest a service serving static files, such as `tower_http`'s `ServeDir`,
+    /// at `path`, with the policy of [`CacheControlLayer::static_assets`].
+    fn cache_static<T>(self, path: &str, service: T) -> Self
+    where
+        CacheControl<T>: Service<Request, Error = Infallible> + Clone + Send + 'static,
+        <CacheControl<T> as Service<Request>>::Response: IntoResponse,
+        <CacheControl<T> as Service<Request>>::Future: Send + 'static;
+}
+
+impl<S> RouterCacheControlExt<S> for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn nest_cached(
+        self,
+        path: &str,
+        router: Router<S>,
+        layer: impl Into<CacheControlLayer>,
+    ) -> Self {
+        self.nest(path, router.layer(layer.into()))
+    }
+
+    fn nest_service_cached<T>(
+        self,
+        path: &str,
+        service: T,
+        layer: impl Into<CacheControlLayer>,
+    ) -> Self
+    where
+        CacheControl<T>: Service<Request, Error = Infallible> + Clone + Send + 'static,
+        <CacheControl<T> as Service<Request>>::Response: IntoResponse,
+        <CacheControl<T> as Service<Request>>::Future: Send + 'static,
+    {
+        self.nest_service(path, layer.into().layer(service))
+    }
+
+    fn cache_static<T>(self, path: &str, service: T) -> Self
+    where
+        CacheControl<T>: Service<Request, Error = Infallible> + Clone + Send + 'static,
+        <CacheControl<T> as Service<Request>>::Response: IntoResponse,
+        <CacheControl<T> as Service<Request>>::Future: Send + 'static,
+    {
+        self.nest_service_cached(path, service, CacheControlLayer::static_assets())
+    }
+}
@@ -0,0 +1,475 @@
+use std::{
+    fmt,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+use http::{
+    header::{CONTENT_DISPOSITION, CONTENT_LENGTH},
+    request, response, HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+};
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use crate::{
+    directives::CacheControlDirectives,
+    mime::MimeType,
+    path::{self, Glob},
+};
+
+/// The conditions a response must meet for a rule to apply.
+///
+/// A matcher is built from any number of conditions, all of which must hold.
+/// A matcher without conditions ([`Matcher::any`]) matches every response.
+///
+/// ```rust
+/// use axum_cc::{Matcher, MimeType};
+/// use http::StatusCode;
+///
+/// // Successful CSS or JS responses under `/assets`.
+/// let matcher = Matcher::path_prefix("/assets")
+///     .with_mime(MimeType::CSS)
+///     .with_mime(MimeType::JS)
+///     .with_status(StatusCode::OK);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Matcher {
+    methods: Vec<Method>,
+    mime_types: Vec<MimeType>,
+    /// MIME type families, such as `image/*`.
+    mime_families: Vec<String>,
+    path: Option<PathPattern>,
+    /// Whether the request path must end in a fingerprinted file name.
+    fingerprinted: bool,
+    /// A range of status codes.
+    status: Option<(Bound<u16>, Bound<u16>)>,
+    /// A range of `Content-Length`s, in bytes.
+    content_length: Option<(Bound<u64>, Bound<u64>)>,
+    /// Whether the response must be a `Content-Disposition: attachment`.
+    attachment: bool,
+}
+
+/// How a [`Matcher`] matches the request path.
+#[derive(Debug, Clone)]
+enum PathPattern {
+    Prefix(String),
+    Glob(Glob),
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Match every response.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Match responses to requests with the given method.
+    pub fn method(method: Method) -> Self {
+        Self::any().with_method(method)
+    }
+
+    /// Match responses with the given MIME type.
+    pub fn mime(mime: MimeType) -> Self {
+        Self::any().with_mime(mime)
+    }
+
+    /// Match responses with a MIME type in the given family, such as
+    /// `image/*`.
+    pub fn mime_family(family: &str) -> Self {
+        Self::any().with_mime_family(family)
+    }
+
+    /// Match responses to requests beneath the given path.
+    pub fn path_prefix(prefix: impl AsRef<str>) -> Self {
+        Self::any().with_path_prefix(prefix)
+    }
+
+    /// Match responses to requests whose path matches the given glob.
+    pub fn path_glob(pattern: impl AsRef<str>) -> Self {
+        Self::any().with_path_glob(pattern)
+    }
+
+    /// Match responses to requests whose path matches the given regex.
+    #[cfg(feature = "regex")]
+    pub fn path_regex(regex: Regex) -> Self {
+        Self::any().with_path_regex(regex)
+    }
+
+    /// Match responses to requests for fingerprinted file names.
+    pub fn fingerprinted() -> Self {
+        Self::any().with_fingerprinted()
+    }
+
+    /// Match responses with the given status code.
+    pub fn status(status: StatusCode) -> Self {
+        Self::any().with_status(status)
+    }
+
+    /// Match responses with a status code in the given range.
+    pub fn status_range(range: impl RangeBounds<u16>) -> Self {
+        Self::any().with_status_range(range)
+    }
+
+    /// Match responses marked `Content-Disposition: attachment`.
+    pub fn attachment() -> Self {
+        Self::any().with_attachment()
+    }
+
+    /// Match responses whose `Content-Length` is in the given range, in
+    /// bytes.
+    pub fn content_length(range: impl RangeBounds<u64>) -> Self {
+        Self::any().with_content_length(range)
+    }
+
+    /// Also match responses to requests with the given method. A matcher with
+    /// several methods matches requests with any of them.
+    ///
+    /// Method conditions only narrow down the methods the layer applies to
+    /// (`GET` and `HEAD` by default), so to write rules for other methods,
+    /// allow them with [`CacheControlLayer::with_methods`] first:
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, Matcher, MimeType};
+    /// use http::Method;
+    ///
+    /// const METHODS: [Method; 3] = [Method::GET, Method::HEAD, Method::POST];
+    ///
+    /// // Cache pages from `GET` for a minute, but never cache `POST` results.
+    /// let layer = CacheControlLayer::default()
+    ///     .with_methods(&METHODS)
+    ///     .rule(
+    ///         Matcher::method(Method::GET).with_mime(MimeType::HTML),
+    ///         Duration::from_secs(60),
+    ///     )
+    ///     .rule(
+    ///         Matcher::method(Method::POST),
+    ///         CacheControlDirectives::new().no_store(),
+    ///     );
+    /// ```
+    ///
+    /// [`CacheControlLayer::with_methods`]: crate::CacheControlLayer::with_methods
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    /// Also match responses with the given MIME type. A matcher with several
+    /// MIME types matches responses with any of them.
+    pub fn with_mime(mut self, mime: MimeType) -> Self {
+        self.mime_types.push(mime);
+        self
+    }
+
+    /// Also match responses with a MIME type in the given family, written as
+    /// either a top-level type (`image`) or a wildcard (`image/*`). This
+    /// combines with [`Matcher::with_mime`], so a matcher matches responses
+    /// with any of its MIME types or families.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlLayer, Matcher};
+    ///
+    /// let layer = CacheControlLayer::default().rule(
+    ///     Matcher::mime_family("image/*")
+    ///         .with_mime_family("font/*")
+    ///         .with_mime_family("audio/*"),
+    ///     Duration::from_secs(60 * 60 * 24 * 30),
+    /// );
+    /// ```
+    pub fn with_mime_family(mut self, family: &str) -> Self {
+        self.mime_families.push(family.to_owned());
+        self
+    }
+
+    /// Only match responses to requests beneath the given path.
+    ///
+    /// Both the prefix and the request path are normalized before matching
+    /// (percent-decoding, collapsing slashes, and resolving `.` and `..`), so
+    /// `/assets/../admin` does not match `/assets`. Matching is done per
+    /// segment: `/assets` (or `/assets/`) matches `/assets` and
+    /// `/assets/app.js`, but not `/assets.json`. A path condition replaces
+    /// any previous one.
+    pub fn with_path_prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        let mut prefix = path::normalize(prefix.as_ref());
+
+        if prefix.len() > 1 && prefix.ends_with('/') {
+            prefix.pop();
+        }
+
+        self.path = Some(PathPattern::Prefix(prefix));
+        self
+    }
+
+    /// Only match responses to requests whose path matches the given glob.
+    ///
+    /// In the pattern, `*` matches any characters within a path segment, `?`
+    /// matches a single character, and a `**` segment matches any number of
+    /// segments:
+    ///
+    /// - `/assets/**` matches `/assets`, `/assets/app.js`, and
+    ///   `/assets/fonts/inter.woff2`
+    /// - `/assets/*.js` matches `/assets/app.js`, but not
+    ///   `/assets/vendor/lib.js`
+    /// - `/**/*.map` matches source maps anywhere
+    ///
+    /// The request path is normalized before matching, as with
+    /// [`Matcher::with_path_prefix`]. A path condition replaces any previous
+    /// one.
+    pub fn with_path_glob(mut self, pattern: impl AsRef<str>) -> Self {
+        self.path = Some(PathPattern::Glob(Glob::new(pattern.as_ref())));
+        self
+    }
+
+    /// Only match responses to requests whose path matches the given regex.
+    ///
+    /// The regex is compiled once, up front, and tested against the
+    /// normalized request path (see [`Matcher::with_path_prefix`]). It is not
+    /// anchored, so use `^` and `$` to match the whole path:
+    ///
+    /// ```rust
+    /// use axum_cc::Matcher;
+    /// use regex::Regex;
+    ///
+    /// // Versioned bundles, like `/app.3f9a1c2e.js`.
+    /// let matcher = Matcher::path_regex(Regex::new(r"^/[^/]+\.[0-9a-f]{8}\.js$").unwrap());
+    /// ```
+    ///
+    /// A path condition replaces any previous one.
+    #[cfg(feature = "regex")]
+    pub fn with_path_regex(mut self, regex: Regex) -> Self {
+        self.path = Some(PathPattern::Regex(regex));
+        self
+    }
+
+    /// Only match responses to requests for file names that contain a
+    /// content hash, such as `app.3fa9c2.js` or `style-5d41402a.css`, whose
+    /// content can never change without their URL changing too.
+    ///
+    /// A hash is a part of the file name, delimited by `.`, `-`, or `_`, of
+    /// at least six hexadecimal digits. It must include both a decimal digit
+    /// and a letter, so names like `report-20240101.pdf` or `12345678.json`
+    /// aren't mistaken for fingerprinted ones. For other
+    /// hashing schemes, match the path with [`Matcher::path_glob`] or
+    /// `Matcher::path_regex` instead.
+    ///
+    /// This combines with a path condition, rather than replacing it:
+    ///
+    /// ```rust
+    /// use axum_cc::Matcher;
+    ///
+    /// let matcher = Matcher::path_prefix("/assets").with_fingerprinted();
+    /// ```
+    pub fn with_fingerprinted(mut self) -> Self {
+        self.fingerprinted = true;
+        self
+    }
+
+    /// Only match responses marked `Content-Disposition: attachment`, such
+    /// as generated downloads.
+    ///
+    /// ```rust
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, Matcher};
+    ///
+    /// let layer = CacheControlLayer::new().rule(
+    ///     Matcher::attachment(),
+    ///     CacheControlDirectives::new().private().no_cache(),
+    /// );
+    /// ```
+    pub fn with_attachment(mut self) -> Self {
+        self.attachment = true;
+        self
+    }
+
+    /// Only match responses with the given status code. A status condition
+    /// replaces any previous one.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some((
+            Bound::Included(status.as_u16()),
+            Bound::Included(status.as_u16()),
+        ));
+        self
+    }
+
+    /// Only match responses with a status code in the given range.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlLayer, Matcher};
+    ///
+    /// let layer = CacheControlLayer::new()
+    ///     // Cache successful responses for a day...
+    ///     .rule(Matcher::status_range(200..300), Duration::from_secs(86400))
+    ///     // ...and not-founds only briefly.
+    ///     .rule(Matcher::status_range(404..=404), Duration::from_secs(60));
+    /// ```
+    ///
+    /// An empty range, like `300..300`, matches no status. A status
+    /// condition replaces any previous one.
+    pub fn with_status_range(mut self, range: impl RangeBounds<u16>) -> Self {
+        self.status = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+        self
+    }
+
+    /// Only match responses whose `Content-Length` is in the given range, in
+    /// bytes.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use axum_cc::{CacheControlDirectives, CacheControlLayer, Matcher};
+    ///
+    /// const YEAR: Duration = Duration::from_secs(60 * 60 * 24 * 365);
+    ///
+    /// let layer = CacheControlLayer::default()
+    ///     // Cache assets under 5 MB for a year...
+    ///     .rule(
+    ///         Matcher::path_prefix("/assets").with_content_length(..5_000_000),
+    ///         CacheControlDirectives::from(YEAR).immutable(),
+    ///     )
+    ///     // ...and ask proxies not to recompress larger ones.
+    ///     .rule(
+    ///         Matcher::path_prefix("/assets"),
+    ///         CacheControlDirectives::from(YEAR).no_transform(),
+    ///     );
+    /// ```
+    ///
+    /// Responses without a `Content-Length`, such as streamed ones, never
+    /// match a length condition, and neither does any response for an empty
+    /// range like `..0`. A length condition replaces any previous one.
+    pub fn with_content_length(mut self, range: impl RangeBounds<u64>) -> Self {
+        self.content_length = Some((range.start_bound().cloned(), range.end_bound().cloned()));
+        self
+    }
+
+    /// Returns `true` if matching needs the request path.
+    pub(crate) fn uses_path(&self) -> bool {
+        self.path.is_some() || self.fingerprinted
+    }
+
+    /// Returns `true` if the matcher has a status condition.
+    pub(crate) fn uses_status(&self) -> bool {
+        self.status.is_some()
+    }
+
+    pub(crate) fn matches(&self, cx: &MatchContext<'_>) -> bool {
+        if !self.methods.is_empty() && !self.methods.contains(cx.method) {
+            return false;
+        }
+
+        if !(self.mime_types.is_empty() && self.mime_families.is_empty()) {
+            let matched = cx.mime.is_some_and(|mime| {
+                self.mime_types.contains(mime)
+                    || self.mime_families.iter().any(|family| mime.matches(family))
+            });
+
+            if !matched {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = &self.path {
+            let matched = cx.path.is_some_and(|path| match pattern {
+                PathPattern::Prefix(prefix) => path::has_prefix(path, prefix),
+                PathPattern::Glob(glob) => glob.matches(path),
+                #[cfg(feature = "regex")]
+                PathPattern::Regex(regex) => regex.is_match(path),
+            });
+
+            if !matched {
+                return false;
+            }
+        }
+
+        if self.fingerprinted && !cx.path.is_some_and(path::is_fingerprinted) {
+            return false;
+        }
+
+        if let Some(range) = self.status {
+            if !range.contains(&cx.status.as_u16()) {
+                return false;
+            }
+        }
+
+        if self.attachment && !cx.attachment {
+            return false;
+        }
+
+        if let Some(range) = self.content_length {
+            if !cx
+                .content_length
+                .is_some_and(|length| range.contains(&length))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// What a [`Matcher`] is evaluated against.
+pub(crate) struct MatchContext<'a> {
+    pub method: &'a Method,
+    /// The normalized request path, if any rule needs it.
+    pub path: Option<&'a str>,
+    pub mime: Option<&'a MimeType>,
+    pub status: StatusCode,
+    pub content_length: Option<u64>,
+    /// Whether the response is a `Content-Disposition: attachment`.
+    pub attachment: bool,
+}
+
+impl MatchContext<'_> {
+    /// Parse the `Content-Length` of a response from its headers.
+    pub fn content_length(headers: &HeaderMap) -> Option<u64> {
+        headers
+            .get(CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Returns `true` if a response's `Content-Disposition` makes it an
+    /// attachment.
+    pub fn attachment(headers: &HeaderMap) -> bool {
+        headers.get(CONTENT_DISPOSITION).is_some_and(|value| {
+            let kind = value
+                .as_bytes()
+                .split(|&b| b == b';')
+                .next()
+                .unwrap_or_default();
+            kind.trim_ascii().eq_ignore_ascii_case(b"attachment")
+        })
+    }
+}
+
+type KeyFnInner = dyn Fn(&request::Parts, &response::Parts) -> Vec<String> + Send + Sync;
+
+/// Derives the `Surrogate-Key` values of a response.
+#[derive(Clone)]
+pub(crate) struct KeyFn(pub Arc<KeyFnInner>);
+
+impl fmt::Debug for KeyFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyFn")
+    }
+}
+
+/// A matcher and the directives to emit when it matches.
+#[derive(Debug, Clone)]
+pub(crate) struct Rule {
+    pub matcher: Matcher,
+    pub directives: CacheControlDirectives,
+    pub surrogate_keys: Option<KeyFn>,
+    /// The header names added to `Vary` with `rule_with_vary`.
+    pub vary: Vec<HeaderName>,
+    /// The name given to the rule with `named_rule`.
+    pub name: Option<HeaderValue>,
+}
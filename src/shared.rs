@@ -0,0 +1,98 @@
+use std::{
+    sync::{Arc, PoisonError, RwLock},
+    task::{Context, Poll},
+};
+
+use axum_core::response::Response;
+use http::Request;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::layer::{CacheControlLayer, ResponseFuture};
+
+/// A [`CacheControlLayer`] that can be replaced while the server is running.
+///
+/// Clones of a `SharedCacheControlLayer`, and the services it builds, all
+/// share one configuration, so a clone kept outside the router works as a
+/// handle for [`reload`](SharedCacheControlLayer::reload). Requests already
+/// in flight finish with the configuration they started with.
+///
+/// ```rust
+/// use axum::{routing::get, Router};
+/// use axum_cc::{CacheControlDirectives, CacheControlLayer, SharedCacheControlLayer};
+///
+/// let layer = SharedCacheControlLayer::new(CacheControlLayer::new());
+/// let handle = layer.clone();
+///
+/// let app: Router = Router::new()
+///     .route("/", get(|| async { "hello" }))
+///     .layer(layer);
+///
+/// // During an incident, stop caching anything.
+/// handle.reload(
+///     CacheControlLayer::new().with_directives(CacheControlDirectives::new().no_store()),
+/// );
+/// ```
+///
+/// With the `serde` feature enabled, `reload` also accepts a
+/// [`CacheControlConfig`](crate::config::CacheControlConfig).
+#[derive(Debug, Clone)]
+pub struct SharedCacheControlLayer {
+    current: Arc<RwLock<Arc<CacheControlLayer>>>,
+}
+
+impl SharedCacheControlLayer {
+    /// Create a new `SharedCacheControlLayer` that starts out as `layer`.
+    pub fn new(layer: CacheControlLayer) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(layer.precompute()))),
+        }
+    }
+
+    /// Replace the configuration of this layer and every clone of it.
+    pub fn reload(&self, layer: impl Into<CacheControlLayer>) {
+        let layer = Arc::new(layer.into().precompute());
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = layer;
+    }
+
+    pub(crate) fn current(&self) -> Arc<CacheControlLayer> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl<S> Layer<S> for SharedCacheControlLayer {
+    type Service = SharedCacheControl<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SharedCacheControl {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SharedCacheControl<S> {
+    inner: S,
+    layer: SharedCacheControlLayer,
+}
+
+impl<S, T, U> Service<Request<T>> for SharedCacheControl<S>
+where
+    S: Service<Request<T>, Response = Response<U>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        ResponseFuture::new(self.layer.current(), &mut self.inner, req)
+    }
+}
@@ -0,0 +1,195 @@
+//! Content sniffing for responses without a usable `Content-Type`.
+//!
+//! [`SniffLayer`] buffers the first bytes of such responses, guesses their
+//! type with [`MimeType::sniff`], and sets `Content-Type` accordingly, so
+//! that a [`CacheControlLayer`] wrapping it can match the response by MIME
+//! type. It has to sit between the `CacheControlLayer` and the service:
+//!
+//! ```rust
+//! use axum::{routing::get, Router};
+//! use axum_cc::{sniff::SniffLayer, CacheControlLayer};
+//!
+//! // Layers added later wrap those added before them.
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "hello" }))
+//!     .layer(SniffLayer::new())
+//!     .layer(CacheControlLayer::new());
+//! ```
+//!
+//! [`CacheControlLayer`]: crate::CacheControlLayer
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum_core::response::Response;
+use futures_core::ready;
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    response, HeaderMap, HeaderValue, Request,
+};
+use http_body::Body;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    body::{Buffer, BufferedBody},
+    mime::MimeType,
+};
+
+/// The number of bytes buffered by default, enough for every format
+/// [`MimeType::sniff`] recognizes.
+const DEFAULT_LIMIT: usize = 512;
+
+/// A layer that sets `Content-Type` on responses without a usable one by
+/// sniffing their body.
+///
+/// Responses are sniffed if they have no `Content-Type`, one that can't be
+/// parsed, or `application/octet-stream`, and no `Content-Encoding`. Only
+/// those responses are held back while the first bytes are buffered; the
+/// buffered bytes are then replayed ahead of the rest of the body. If
+/// nothing matches, the response is left untouched.
+#[derive(Debug, Clone, Copy)]
+pub struct SniffLayer {
+    limit: usize,
+}
+
+impl Default for SniffLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SniffLayer {
+    /// Create a new `SniffLayer` that buffers up to 512 bytes.
+    pub fn new() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+        }
+    }
+
+    /// Set the number of bytes to buffer before sniffing.
+    ///
+    /// Bodies that end sooner are sniffed once they end.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+impl<S> Layer<S> for SniffLayer {
+    type Service = Sniff<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Sniff {
+            inner,
+            limit: self.limit,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sniff<S> {
+    inner: S,
+    limit: usize,
+}
+
+impl<S, T, B> Service<Request<T>> for Sniff<S>
+where
+    S: Service<Request<T>, Response = Response<B>>,
+    B: Body,
+{
+    type Response = Response<BufferedBody<B>>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<T>) -> Self::Future {
+        ResponseFuture {
+            response_future: self.inner.call(req),
+            limit: self.limit,
+            buffering: None,
+        }
+    }
+}
+
+/// Returns `true` if the response's `Content-Type` should be sniffed.
+fn needs_sniffing(headers: &HeaderMap) -> bool {
+    if headers.contains_key(CONTENT_ENCODING) {
+        return false;
+    }
+
+    match headers.get(CONTENT_TYPE) {
+        Some(value) => {
+            value.as_bytes() == b"application/octet-stream" || MimeType::try_from(value).is_err()
+        }
+        None => true,
+    }
+}
+
+pin_project! {
+    pub struct ResponseFuture<F, B>
+    where
+        B: Body,
+    {
+        #[pin]
+        response_future: F,
+        limit: usize,
+        // The response, held back while its body is buffered.
+        buffering: Option<(response::Parts, Buffer<B>)>,
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F, B>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body,
+{
+    type Output = Result<Response<BufferedBody<B>>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if this.buffering.is_none() {
+            let response = ready!(this.response_future.poll(cx))?;
+
+            if *this.limit == 0 || !needs_sniffing(response.headers()) {
+                return Poll::Ready(Ok(response.map(BufferedBody::new)));
+            }
+
+            let (parts, body) = response.into_parts();
+            *this.buffering = Some((parts, Buffer::new(body)));
+        }
+
+        let Some((_, buffer)) = this.buffering else {
+            unreachable!("the response is buffered above");
+        };
+
+        ready!(buffer.poll_fill(cx, *this.limit));
+
+        let Some((mut parts, buffer)) = this.buffering.take() else {
+            unreachable!("the response is buffered above");
+        };
+
+        let mut prefix = Vec::with_capacity(*this.limit);
+
+        for data in buffer.data() {
+            let len = data.len().min(*this.limit - prefix.len());
+            prefix.extend_from_slice(&data[..len]);
+        }
+
+        if let Some(mime) = MimeType::sniff(&prefix) {
+            if let Ok(value) = HeaderValue::from_str(mime.as_str()) {
+                parts.headers.insert(CONTENT_TYPE, value);
+            }
+        }
+
+        Poll::Ready(Ok(Response::from_parts(parts, buffer.into_body())))
+    }
+}
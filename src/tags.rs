@@ -0,0 +1,48 @@
+use http::{HeaderName, HeaderValue};
+
+/// The header used to attach cache tags to a response, which CDNs use for
+/// targeted purging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheTagHeader {
+    /// `Surrogate-Key`, a space-separated list used by Fastly and Varnish.
+    SurrogateKey,
+    /// `Cache-Tag`, a comma-separated list used by Cloudflare and others.
+    CacheTag,
+}
+
+impl CacheTagHeader {
+    pub fn name(&self) -> HeaderName {
+        match self {
+            CacheTagHeader::SurrogateKey => HeaderName::from_static("surrogate-key"),
+            CacheTagHeader::CacheTag => HeaderName::from_static("cache-tag"),
+        }
+    }
+
+    fn separator(&self) -> &str {
+        match self {
+            CacheTagHeader::SurrogateKey => " ",
+            CacheTagHeader::CacheTag => ",",
+        }
+    }
+
+    /// Join `tags` into a header value, leaving out those that are empty,
+    /// contain the separator, or aren't valid in a header. Returns `None` if
+    /// no tags are left.
+    pub fn value<S: AsRef<str>>(&self, tags: &[S]) -> Option<HeaderValue> {
+        let tags: Vec<_> = tags
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|tag| {
+                !tag.is_empty()
+                    && !tag.contains(self.separator())
+                    && HeaderValue::from_str(tag).is_ok()
+            })
+            .collect();
+
+        if tags.is_empty() {
+            return None;
+        }
+
+        HeaderValue::from_str(&tags.join(self.separator())).ok()
+    }
+}
@@ -0,0 +1,38 @@
+use http::HeaderName;
+
+/// A targeted cache-control field, as defined by RFC 9213.
+///
+/// Targeted fields use the same syntax as `Cache-Control`, but only the
+/// caches they target obey them, in place of `Cache-Control`. This lets a
+/// CDN keep a response longer than browsers do.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TargetedField {
+    /// `CDN-Cache-Control`, for every CDN that supports RFC 9213.
+    Cdn,
+    /// `Cloudflare-CDN-Cache-Control`, for Cloudflare only.
+    Cloudflare,
+    /// `Akamai-Cache-Control`, for Akamai only.
+    Akamai,
+    /// `Vercel-CDN-Cache-Control`, for Vercel only.
+    Vercel,
+    /// `Surrogate-Control`, for Fastly, Varnish, and other surrogates.
+    ///
+    /// It predates RFC 9213 and only understands a few directives, chiefly
+    /// `max-age` and `no-store`, so leave out `public` and the like.
+    SurrogateControl,
+    /// Any other targeted field.
+    Custom(HeaderName),
+}
+
+impl TargetedField {
+    pub fn name(&self) -> HeaderName {
+        match self {
+            TargetedField::Cdn => HeaderName::from_static("cdn-cache-control"),
+            TargetedField::Cloudflare => HeaderName::from_static("cloudflare-cdn-cache-control"),
+            TargetedField::Akamai => HeaderName::from_static("akamai-cache-control"),
+            TargetedField::Vercel => HeaderName::from_static("vercel-cdn-cache-control"),
+            TargetedField::SurrogateControl => HeaderName::from_static("surrogate-control"),
+            TargetedField::Custom(name) => name.clone(),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Helpers for testing `Cache-Control` policies.
+//!
+//! [`TestService`] stands in for a handler, returning a canned response,
+//! and [`oneshot`] drives a service wrapped in a layer to completion, so a
+//! policy can be checked without building an app around it:
+//!
+//! ```rust
+//! use axum_cc::{assert_cache_control, test_util::{self, TestService}, CacheControlLayer};
+//! use tower_layer::Layer;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let service = CacheControlLayer::static_assets()
+//!     .layer(TestService::new().with_content_type("text/css"));
+//!
+//! let response = test_util::oneshot(service, test_util::get("/style.css")).await;
+//! assert_cache_control!(response, "public, max-age=31536000, immutable");
+//! # }
+//! ```
+//!
+//! The module is only compiled with the `test_util` feature, which is meant
+//! to be enabled from `[dev-dependencies]`.
+
+use std::{
+    convert::Infallible,
+    future,
+    task::{Context, Poll},
+};
+
+use axum_core::{body::Body, response::Response};
+use http::{header::CACHE_CONTROL, HeaderMap, HeaderName, HeaderValue, Request, StatusCode};
+use tower_service::Service;
+
+/// A service that answers every request with the same response.
+///
+/// The response has an empty body and, unless configured otherwise, a
+/// `200 OK` status and no headers.
+#[derive(Debug, Clone)]
+pub struct TestService {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: String,
+}
+
+impl TestService {
+    /// Create a new `TestService` that responds with an empty `200 OK`.
+    pub fn new() -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: String::new(),
+        }
+    }
+
+    /// Respond with the given status.
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Respond with the given `Content-Type`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `content_type` isn't a valid header value.
+    pub fn with_content_type(self, content_type: &str) -> Self {
+        self.with_header("content-type", content_type)
+    }
+
+    /// Add a header to the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `value` isn't valid in a header.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid header name");
+        let value = HeaderValue::try_from(value).expect("invalid header value");
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Respond with the given body.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+}
+
+impl Default for TestService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B> Service<Request<B>> for TestService {
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Request<B>) -> Self::Future {
+        let mut response = Response::new(self.body.clone());
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        future::ready(Ok(response))
+    }
+}
+
+/// Build a `GET` request for `uri`, with an empty body.
+///
+/// # Panics
+///
+/// Panics if `uri` isn't a valid URI.
+pub fn get(uri: &str) -> Request<Body> {
+    Request::get(uri)
+        .body(Body::empty())
+        .expect("invalid request URI")
+}
+
+/// Wait for `service` to be ready, call it with `request`, and wait for the
+/// response, like `tower::ServiceExt::oneshot` does.
+///
+/// Works with any service that can't fail, such as an axum `Router`.
+pub async fn oneshot<S, R>(mut service: S, request: R) -> S::Response
+where
+    S: Service<R, Error = Infallible>,
+{
+    let Ok(()) = future::poll_fn(|cx| service.poll_ready(cx)).await;
+    let Ok(response) = service.call(request).await;
+    response
+}
+
+/// The `Cache-Control` header of `response`, if it has a readable one.
+pub fn cache_control<B>(response: &Response<B>) -> Option<&str> {
+    response.headers().get(CACHE_CONTROL)?.to_str().ok()
+}
+
+/// Assert that a response's `Cache-Control` header is the given value, or
+/// that it has none.
+///
+/// ```rust
+/// use axum::response::{IntoResponse, Response};
+/// use axum_cc::{assert_cache_control, NoCache};
+///
+/// let response: Response = NoCache("secret").into_response();
+/// assert_cache_control!(response, "no-store");
+///
+/// let response: Response = "public".into_response();
+/// assert_cache_control!(response, None);
+/// ```
+#[macro_export]
+macro_rules! assert_cache_control {
+    ($response:expr, None $(,)?) => {
+        assert_eq!(
+            $crate::test_util::cache_control(&$response),
+            None,
+            "expected no Cache-Control header",
+        )
+    };
+    ($response:expr, $expected:expr $(,)?) => {
+        assert_eq!(
+            $crate::test_util::cache_control(&$response),
+            Some($expected),
+            "unexpected Cache-Control header",
+        )
+    };
+}
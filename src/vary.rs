@@ -0,0 +1,35 @@
+use http::{header::VARY, HeaderMap, HeaderName, HeaderValue};
+
+/// Add `name` to the response's `Vary` header, keeping any existing values.
+///
+/// Nothing is added if the header already lists `name` or is `*`.
+pub(crate) fn append(headers: &mut HeaderMap, name: &HeaderName) {
+    let mut existing = Vec::new();
+
+    for value in headers.get_all(VARY) {
+        let Ok(value) = value.to_str() else {
+            // Leave headers we can't parse alone rather than risk dropping
+            // part of them.
+            return;
+        };
+
+        for field in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            if field == "*" || field.eq_ignore_ascii_case(name.as_str()) {
+                return;
+            }
+
+            existing.push(field);
+        }
+    }
+
+    if existing.is_empty() {
+        headers.insert(VARY, HeaderValue::from_name(name.clone()));
+        return;
+    }
+
+    existing.push(name.as_str());
+
+    if let Ok(value) = HeaderValue::from_str(&existing.join(", ")) {
+        headers.insert(VARY, value);
+    }
+}
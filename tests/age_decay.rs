@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+async fn cache_control(age: Option<&str>) -> Option<String> {
+    let mut inner = TestService::new().with_content_type("text/css");
+
+    if let Some(age) = age {
+        inner = inner.with_header("age", age);
+    }
+
+    let service = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(600))
+        .with_age_decay()
+        .layer(inner);
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn subtracts_age_from_max_age() {
+    for (age, expected) in [
+        ("0", "public, max-age=600"),
+        ("1", "public, max-age=599"),
+        ("599", "public, max-age=1"),
+        ("600", "public, max-age=0"),
+    ] {
+        assert_eq!(
+            cache_control(Some(age)).await.as_deref(),
+            Some(expected),
+            "{age}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn clamps_ages_beyond_max_age_to_zero() {
+    for age in ["601", "86400", "18446744073709551615"] {
+        assert_eq!(
+            cache_control(Some(age)).await.as_deref(),
+            Some("public, max-age=0"),
+            "{age}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn ignores_missing_and_invalid_ages() {
+    for age in [None, Some("-5"), Some("soon"), Some("")] {
+        assert_eq!(
+            cache_control(age).await.as_deref(),
+            Some("public, max-age=600"),
+            "{age:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn only_decays_when_enabled() {
+    let service = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(600))
+        .layer(
+            TestService::new()
+                .with_content_type("text/css")
+                .with_header("age", "100"),
+        );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(
+        test_util::cache_control(&response),
+        Some("public, max-age=600")
+    );
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer, Downgrade, Matcher,
+};
+use tower_layer::Layer;
+
+const ATTACHMENT: &str = r#"attachment; filename="report.css""#;
+
+async fn cache_control(layer: CacheControlLayer, disposition: Option<&str>) -> Option<String> {
+    let mut service = TestService::new().with_content_type("text/css");
+    if let Some(disposition) = disposition {
+        service = service.with_header("content-disposition", disposition);
+    }
+
+    let response = test_util::oneshot(layer.layer(service), test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn attachments_get_the_policy_by_default() {
+    assert_eq!(
+        cache_control(CacheControlLayer::new(), Some(ATTACHMENT))
+            .await
+            .as_deref(),
+        Some("public, max-age=31536000")
+    );
+}
+
+#[tokio::test]
+async fn attachments_can_be_downgraded() {
+    let layer = CacheControlLayer::new().with_attachment_downgrade(Downgrade::NoStore);
+
+    for (disposition, expected) in [
+        (Some(ATTACHMENT), "no-store"),
+        (Some("ATTACHMENT"), "no-store"),
+        (Some("inline"), "public, max-age=31536000"),
+        (None, "public, max-age=31536000"),
+    ] {
+        assert_eq!(
+            cache_control(layer.clone(), disposition).await.as_deref(),
+            Some(expected),
+            "{disposition:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn rules_match_attachments() {
+    let layer = CacheControlLayer::default().rule(Matcher::attachment(), Duration::from_secs(60));
+
+    assert_eq!(
+        cache_control(layer.clone(), Some(ATTACHMENT))
+            .await
+            .as_deref(),
+        Some("public, max-age=60")
+    );
+    assert_eq!(cache_control(layer, Some("inline")).await, None);
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, Downgrade,
+};
+use http::HeaderValue;
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(60))
+        .with_s_maxage(Duration::from_secs(600))
+}
+
+async fn authorized(layer: CacheControlLayer, authorized: bool) -> http::Response<String> {
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let mut request = test_util::get("/");
+    if authorized {
+        request
+            .headers_mut()
+            .insert("authorization", HeaderValue::from_static("Bearer token"));
+    }
+
+    test_util::oneshot(service, request).await
+}
+
+#[tokio::test]
+async fn authorized_requests_get_the_policy_by_default() {
+    assert_cache_control!(
+        authorized(layer(), true).await,
+        "public, max-age=60, s-maxage=600"
+    );
+}
+
+#[tokio::test]
+async fn private_drops_s_maxage() {
+    let layer = layer().with_authorization_downgrade(Downgrade::Private);
+    assert_cache_control!(authorized(layer, true).await, "private, max-age=60");
+}
+
+#[tokio::test]
+async fn no_store() {
+    let layer = layer().with_authorization_downgrade(Downgrade::NoStore);
+    assert_cache_control!(authorized(layer, true).await, "no-store");
+}
+
+#[tokio::test]
+async fn skip_leaves_the_response_untouched() {
+    let layer = layer().with_authorization_downgrade(Downgrade::Skip);
+    assert_cache_control!(authorized(layer, true).await, None);
+}
+
+#[tokio::test]
+async fn anonymous_requests_are_unaffected() {
+    let layer = layer().with_authorization_downgrade(Downgrade::NoStore);
+    assert_cache_control!(
+        authorized(layer, false).await,
+        "public, max-age=60, s-maxage=600"
+    );
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use axum_cc::{CacheControlDirectives, CacheControlError, CacheControlLayer, Matcher, MimeType};
+
+#[test]
+fn empty_directives_have_no_header_value() {
+    assert_eq!(
+        CacheControlDirectives::new().header_value(),
+        Err(CacheControlError::EmptyDirectives)
+    );
+    assert_eq!(
+        CacheControlDirectives::new().no_cache().header_value(),
+        Ok(http::HeaderValue::from_static("no-cache"))
+    );
+}
+
+#[test]
+fn build_rejects_empty_directives() {
+    let layer = CacheControlLayer::new().with_directives(CacheControlDirectives::new());
+    assert_eq!(
+        layer.build().unwrap_err(),
+        CacheControlError::EmptyDirectives
+    );
+
+    let layer = CacheControlLayer::default().rule(Matcher::any(), CacheControlDirectives::new());
+    assert_eq!(
+        layer.build().unwrap_err(),
+        CacheControlError::EmptyDirectives
+    );
+
+    let layer =
+        CacheControlLayer::default().with_policy(MimeType::HTML, CacheControlDirectives::new());
+    assert_eq!(
+        layer.build().unwrap_err(),
+        CacheControlError::EmptyDirectives
+    );
+}
+
+#[test]
+fn build_rejects_layers_that_match_nothing() {
+    assert_eq!(
+        CacheControlLayer::default().build().unwrap_err(),
+        CacheControlError::NoPolicies
+    );
+}
+
+#[test]
+fn build_accepts_sound_layers() {
+    let layer = CacheControlLayer::default().rule(Matcher::any(), Duration::from_secs(60));
+    assert!(layer.build().is_ok());
+    assert!(CacheControlLayer::new().build().is_ok());
+}
@@ -0,0 +1,58 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, CacheTagHeader,
+};
+use tower_layer::Layer;
+
+async fn fetch(layer: CacheControlLayer, content_type: &str) -> http::Response<String> {
+    let service = layer.layer(TestService::new().with_content_type(content_type));
+    test_util::oneshot(service, test_util::get("/")).await
+}
+
+#[tokio::test]
+async fn surrogate_keys_are_space_separated() {
+    let layer = CacheControlLayer::new()
+        .with_cache_tags(CacheTagHeader::SurrogateKey, &["static", "assets"]);
+
+    let response = fetch(layer, "text/css").await;
+    assert_cache_control!(response, "public, max-age=31536000");
+    assert_eq!(response.headers()["surrogate-key"], "static assets");
+}
+
+#[tokio::test]
+async fn cache_tags_are_comma_separated() {
+    let layer =
+        CacheControlLayer::new().with_cache_tags(CacheTagHeader::CacheTag, &["static", "assets"]);
+
+    let response = fetch(layer, "text/css").await;
+    assert_eq!(response.headers()["cache-tag"], "static,assets");
+}
+
+#[tokio::test]
+async fn unmatched_responses_get_no_tags() {
+    let layer = CacheControlLayer::new().with_cache_tags(CacheTagHeader::SurrogateKey, &["static"]);
+
+    let response = fetch(layer, "text/html").await;
+    assert_cache_control!(response, None);
+    assert!(!response.headers().contains_key("surrogate-key"));
+}
+
+#[tokio::test]
+async fn invalid_tags_are_dropped() {
+    let layer =
+        CacheControlLayer::new().with_cache_tags(CacheTagHeader::SurrogateKey, &["static\nassets"]);
+
+    let response = fetch(layer, "text/css").await;
+    assert_cache_control!(response, "public, max-age=31536000");
+    assert!(!response.headers().contains_key("surrogate-key"));
+}
+
+#[tokio::test]
+async fn tags_containing_the_separator_are_dropped() {
+    let layer =
+        CacheControlLayer::new().with_cache_tags(CacheTagHeader::CacheTag, &["static", "a,b"]);
+
+    let response = fetch(layer, "text/css").await;
+    assert_eq!(response.headers()["cache-tag"], "static");
+}
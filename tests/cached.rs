@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use axum::{response::AppendHeaders, routing::get, Router};
+use axum_cc::{
+    assert_cache_control, test_util, CacheControlDirectives, CacheControlLayer, Cached, Downgrade,
+    NoCache, OverwriteBehavior,
+};
+use http::header::SET_COOKIE;
+
+fn cached() -> Cached<&'static str> {
+    Cached::new(
+        CacheControlDirectives::new()
+            .public()
+            .with_max_age(Duration::from_secs(60)),
+        "hello",
+    )
+}
+
+fn app(layer: CacheControlLayer) -> Router {
+    Router::new()
+        .route("/cached", get(|| async { cached() }))
+        .route("/no-cache", get(|| async { NoCache("balance") }))
+        .route(
+            "/cookie",
+            get(|| async { Cached(cached().0, (AppendHeaders([(SET_COOKIE, "id=1")]), "hi")) }),
+        )
+        .layer(layer)
+}
+
+#[tokio::test]
+async fn wrappers_set_the_header_without_a_layer() {
+    let app = Router::new()
+        .route("/cached", get(|| async { cached() }))
+        .route("/no-cache", get(|| async { NoCache("balance") }));
+
+    let response = test_util::oneshot(app.clone(), test_util::get("/cached")).await;
+    assert_cache_control!(response, "public, max-age=60");
+
+    let response = test_util::oneshot(app, test_util::get("/no-cache")).await;
+    assert_cache_control!(response, "no-store");
+}
+
+#[tokio::test]
+async fn handler_directives_win_over_the_layer() {
+    for overwrite in [
+        OverwriteBehavior::SkipIfPresent,
+        OverwriteBehavior::Always,
+        OverwriteBehavior::Merge,
+    ] {
+        let layer = CacheControlLayer::new()
+            .with_default_policy(Duration::from_secs(3600))
+            .with_overwrite_behavior(overwrite);
+
+        let response = test_util::oneshot(app(layer.clone()), test_util::get("/cached")).await;
+        assert_cache_control!(response, "public, max-age=60");
+
+        let response = test_util::oneshot(app(layer), test_util::get("/no-cache")).await;
+        assert_cache_control!(response, "no-store");
+    }
+}
+
+#[tokio::test]
+async fn cookies_downgrade_handler_directives() {
+    for overwrite in [
+        OverwriteBehavior::SkipIfPresent,
+        OverwriteBehavior::Always,
+        OverwriteBehavior::Merge,
+    ] {
+        let layer = CacheControlLayer::new().with_overwrite_behavior(overwrite);
+
+        let response = test_util::oneshot(app(layer), test_util::get("/cookie")).await;
+        assert_cache_control!(response, "no-store");
+    }
+
+    let layer = CacheControlLayer::new().with_set_cookie_downgrade(Downgrade::Private);
+    let response = test_util::oneshot(app(layer), test_util::get("/cookie")).await;
+    assert_cache_control!(response, "private, max-age=60");
+}
+
+#[tokio::test]
+async fn skipped_cookies_keep_handler_directives() {
+    let layer = CacheControlLayer::new().with_set_cookie_downgrade(Downgrade::Skip);
+
+    let response = test_util::oneshot(app(layer), test_util::get("/cookie")).await;
+    assert_cache_control!(response, "public, max-age=60");
+}
@@ -0,0 +1,99 @@
+use axum_cc::{
+    clear_site_data::{ClearSiteDataLayer, SiteData},
+    test_util::{self, TestService},
+    Matcher,
+};
+use axum_core::body::Body;
+use http::{Method, Request, StatusCode};
+use tower_layer::Layer;
+
+fn layer() -> ClearSiteDataLayer {
+    ClearSiteDataLayer::new()
+        .rule(
+            Matcher::path_prefix("/logout").with_method(Method::POST),
+            &[SiteData::Cache, SiteData::Cookies, SiteData::Storage],
+        )
+        .rule(Matcher::path_prefix("/reset"), &[SiteData::All])
+        .rule(Matcher::path_prefix("/logout"), &[SiteData::Cache])
+}
+
+async fn clear_site_data(service: TestService, method: Method, uri: &str) -> Option<String> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+
+    let response = test_util::oneshot(layer().layer(service), request).await;
+    response
+        .headers()
+        .get("clear-site-data")
+        .map(|value| value.to_str().unwrap().to_owned())
+}
+
+#[tokio::test]
+async fn the_first_matching_rule_wins() {
+    assert_eq!(
+        clear_site_data(TestService::new(), Method::POST, "/logout")
+            .await
+            .as_deref(),
+        Some(r#""cache", "cookies", "storage""#)
+    );
+    assert_eq!(
+        clear_site_data(TestService::new(), Method::GET, "/logout")
+            .await
+            .as_deref(),
+        Some(r#""cache""#)
+    );
+    assert_eq!(
+        clear_site_data(TestService::new(), Method::GET, "/reset")
+            .await
+            .as_deref(),
+        Some(r#""*""#)
+    );
+}
+
+#[tokio::test]
+async fn unmatched_requests_are_left_alone() {
+    assert_eq!(
+        clear_site_data(TestService::new(), Method::POST, "/login").await,
+        None
+    );
+}
+
+#[tokio::test]
+async fn existing_headers_are_kept() {
+    let service = TestService::new().with_header("clear-site-data", r#""cookies""#);
+
+    assert_eq!(
+        clear_site_data(service, Method::POST, "/logout")
+            .await
+            .as_deref(),
+        Some(r#""cookies""#)
+    );
+}
+
+#[tokio::test]
+async fn rules_without_data_are_ignored() {
+    let service = ClearSiteDataLayer::new()
+        .rule(Matcher::any(), &[])
+        .layer(TestService::new());
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert!(!response.headers().contains_key("clear-site-data"));
+}
+
+#[tokio::test]
+async fn rules_can_require_a_status() {
+    let layer = ClearSiteDataLayer::new().rule(
+        Matcher::path_prefix("/logout").with_status(StatusCode::OK),
+        &[SiteData::All],
+    );
+
+    for (status, expected) in [(StatusCode::OK, true), (StatusCode::UNAUTHORIZED, false)] {
+        let service = layer.clone().layer(TestService::new().with_status(status));
+
+        let response = test_util::oneshot(service, test_util::get("/logout")).await;
+        assert_eq!(response.headers().contains_key("clear-site-data"), expected);
+    }
+}
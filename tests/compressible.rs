@@ -0,0 +1,55 @@
+use axum_cc::MimeType;
+
+#[test]
+fn text_types_are_compressible() {
+    for mime in [
+        MimeType::CSS,
+        MimeType::CSV,
+        MimeType::EOT,
+        MimeType::HTML,
+        MimeType::ICO,
+        MimeType::JS,
+        MimeType::JSON,
+        MimeType::MANIFEST,
+        MimeType::OTF,
+        MimeType::SVG,
+        MimeType::TEXT,
+        MimeType::TTF,
+        MimeType::WASM,
+        MimeType::XML,
+    ] {
+        assert!(mime.is_compressible(), "{mime:?} should be compressible");
+    }
+}
+
+#[test]
+fn compressed_types_are_not_compressible() {
+    for mime in [
+        MimeType::AVIF,
+        MimeType::GIF,
+        MimeType::JPEG,
+        MimeType::MP3,
+        MimeType::MP4,
+        MimeType::PDF,
+        MimeType::PNG,
+        MimeType::WEBM,
+        MimeType::WEBP,
+        MimeType::WOFF2,
+    ] {
+        assert!(
+            !mime.is_compressible(),
+            "{mime:?} shouldn't be compressible"
+        );
+    }
+}
+
+#[test]
+fn custom_types_are_classified_by_name() {
+    let compressible = |mime: &'static str| MimeType::custom(mime).is_compressible();
+
+    assert!(compressible("text/markdown"));
+    assert!(compressible("application/ld+json"));
+    assert!(compressible("application/atom+xml"));
+    assert!(!compressible("application/zip"));
+    assert!(!compressible("font/woff"));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "serde")]
+
+use axum_cc::{
+    assert_cache_control,
+    config::CacheControlConfig,
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+fn config(json: &str) -> Result<CacheControlConfig, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+#[tokio::test]
+async fn rules_match_paths() {
+    let config = config(
+        r#"{
+            "rules": [
+                {
+                    "path-prefix": "/assets",
+                    "directives": { "visibility": "public", "max-age": "1y", "immutable": true }
+                },
+                {
+                    "path-glob": "/**/*.map",
+                    "directives": { "no-store": true }
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let layer = CacheControlLayer::from_config(config);
+    let service = layer.layer(TestService::new().with_content_type("application/json"));
+
+    let response = test_util::oneshot(service.clone(), test_util::get("/assets/app.js")).await;
+    assert_cache_control!(response, "public, max-age=31536000, immutable");
+
+    let response = test_util::oneshot(service, test_util::get("/maps/app.js.map")).await;
+    assert_cache_control!(response, "no-store");
+}
+
+#[test]
+fn rejects_prefix_and_glob_in_one_rule() {
+    let error = config(
+        r#"{
+            "rules": [
+                {
+                    "path-prefix": "/assets",
+                    "path-glob": "/assets/*.js",
+                    "directives": { "max-age": 60 }
+                }
+            ]
+        }"#,
+    )
+    .unwrap_err();
+
+    assert!(
+        error
+            .to_string()
+            .contains("a rule can't have both `path-prefix` and `path-glob`"),
+        "{error}"
+    );
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer, Matcher,
+};
+use tower_layer::Layer;
+
+async fn cache_control(matcher: Matcher, length: Option<&str>) -> Option<String> {
+    let inner = match length {
+        Some(length) => TestService::new().with_header("content-length", length),
+        None => TestService::new(),
+    };
+    let service = CacheControlLayer::default()
+        .rule(matcher, Duration::from_secs(60))
+        .layer(inner);
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn lengths_in_range_match() {
+    for (length, matched) in [
+        ("0", true),
+        ("4999999", true),
+        ("5000000", false),
+        ("10000000", false),
+    ] {
+        let matcher = Matcher::content_length(..5_000_000);
+        assert_eq!(
+            cache_control(matcher, Some(length)).await.is_some(),
+            matched,
+            "{length}"
+        );
+    }
+
+    for (length, matched) in [
+        ("1023", false),
+        ("1024", true),
+        ("2048", true),
+        ("2049", false),
+    ] {
+        let matcher = Matcher::content_length(1024..=2048);
+        assert_eq!(
+            cache_control(matcher, Some(length)).await.is_some(),
+            matched,
+            "{length}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn missing_lengths_never_match() {
+    let cache_control = cache_control(Matcher::content_length(..), None).await;
+    assert_eq!(cache_control, None);
+}
+
+#[tokio::test]
+#[allow(clippy::reversed_empty_ranges)]
+async fn empty_ranges_match_nothing() {
+    for length in ["0", "1", "100"] {
+        assert_eq!(
+            cache_control(Matcher::content_length(..0), Some(length)).await,
+            None
+        );
+        assert_eq!(
+            cache_control(Matcher::content_length(1..1), Some(length)).await,
+            None
+        );
+        assert_eq!(
+            cache_control(Matcher::content_length(100..0), Some(length)).await,
+            None
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use axum::{
+    response::{Html, IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, MimeType,
+};
+use axum_core::body::Body;
+use http::{
+    header::{ACCEPT, CONTENT_TYPE, VARY},
+    HeaderMap, Request,
+};
+use tower_layer::Layer;
+
+/// Responds with JSON or HTML, depending on `Accept`.
+async fn negotiated(headers: HeaderMap) -> Response {
+    let wants_json = headers
+        .get(ACCEPT)
+        .is_some_and(|accept| accept.as_bytes().starts_with(b"application/json"));
+
+    if wants_json {
+        Json([1, 2, 3]).into_response()
+    } else {
+        Html("<ul></ul>").into_response()
+    }
+}
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .with_mime_types(&[MimeType::HTML, MimeType::JSON])
+        .with_max_age(Duration::from_secs(60))
+        .with_content_negotiation_vary()
+}
+
+fn request(accept: &str) -> Request<Body> {
+    Request::get("/")
+        .header(ACCEPT, accept)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn vary(response: &Response) -> Vec<&str> {
+    response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn varies_negotiated_responses_on_accept() {
+    let app = Router::new().route("/", get(negotiated)).layer(layer());
+
+    for (accept, content_type) in [
+        ("application/json", "application/json"),
+        ("text/html", "text/html; charset=utf-8"),
+    ] {
+        let response = test_util::oneshot(app.clone(), request(accept)).await;
+
+        assert_eq!(response.headers()[CONTENT_TYPE], content_type);
+        assert_cache_control!(response, "public, max-age=60");
+        assert_eq!(vary(&response), ["accept"]);
+    }
+}
+
+#[tokio::test]
+async fn keeps_existing_vary() {
+    let service = layer().layer(
+        TestService::new()
+            .with_content_type("application/json")
+            .with_header("vary", "origin"),
+    );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(
+        response.headers().get_all(VARY).iter().collect::<Vec<_>>(),
+        ["origin, accept"]
+    );
+}
+
+#[tokio::test]
+async fn leaves_uncached_responses_alone() {
+    let service = layer().layer(TestService::new().with_content_type("application/x-unknown"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_cache_control!(response, None);
+    assert!(!response.headers().contains_key(VARY));
+}
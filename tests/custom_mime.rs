@@ -0,0 +1,46 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, MimeType,
+};
+use http::HeaderValue;
+use tower_layer::Layer;
+
+const REPORT: &str = "application/vnd.mycorp.report+json";
+
+#[test]
+fn custom_types_are_normalized() {
+    assert_eq!(MimeType::custom(REPORT).as_str(), REPORT);
+    assert_eq!(
+        MimeType::custom("Application/Vnd.MyCorp.Report+JSON; charset=utf-8").as_str(),
+        REPORT
+    );
+    assert_eq!(
+        MimeType::custom(REPORT),
+        MimeType::custom(REPORT.to_owned())
+    );
+}
+
+#[test]
+fn unknown_types_parse_as_custom() {
+    let header = HeaderValue::from_static("application/vnd.mycorp.report+json; charset=utf-8");
+    assert_eq!(
+        MimeType::try_from(&header).ok(),
+        Some(MimeType::custom(REPORT))
+    );
+}
+
+#[tokio::test]
+async fn custom_types_can_be_matched() {
+    let layer = CacheControlLayer::new().with_mime_types(&[MimeType::custom(REPORT)]);
+
+    let service = layer.clone().layer(
+        TestService::new().with_content_type("application/vnd.mycorp.report+json; charset=utf-8"),
+    );
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+
+    let service = layer.layer(TestService::new().with_content_type("application/vnd.other+json"));
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, None);
+}
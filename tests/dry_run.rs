@@ -0,0 +1,51 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, DryRun,
+};
+use http::header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE, EXPIRES, VARY};
+use tower_layer::Layer;
+
+#[tokio::test]
+async fn leaves_headers_untouched() {
+    let layer = CacheControlLayer::new()
+        .with_dry_run()
+        .with_expires()
+        .with_vary(&[ACCEPT]);
+    let inner = TestService::new()
+        .with_content_type("text/css")
+        .with_header("vary", "origin");
+
+    let expected = test_util::oneshot(inner.clone(), test_util::get("/")).await;
+    let response = test_util::oneshot(layer.layer(inner), test_util::get("/")).await;
+
+    assert_cache_control!(response, None);
+    assert_eq!(response.headers(), expected.headers());
+}
+
+#[tokio::test]
+async fn records_the_headers_it_would_set() {
+    let layer = CacheControlLayer::new()
+        .with_dry_run()
+        .with_expires()
+        .with_vary(&[ACCEPT]);
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    let DryRun(headers) = response.extensions().get::<DryRun>().unwrap();
+
+    assert_eq!(headers[CACHE_CONTROL], "public, max-age=31536000");
+    assert_eq!(headers[VARY], "accept");
+    assert!(headers.contains_key(EXPIRES));
+    assert!(!headers.contains_key(CONTENT_TYPE));
+}
+
+#[tokio::test]
+async fn records_nothing_for_skipped_responses() {
+    let layer = CacheControlLayer::new().with_dry_run();
+    let service = layer.layer(TestService::new().with_content_type("application/x-unknown"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert!(response.extensions().get::<DryRun>().is_none());
+}
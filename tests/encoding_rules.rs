@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use http::{header::VARY, Response};
+use tower_layer::Layer;
+
+async fn respond(encoding: Option<&str>) -> Response<String> {
+    let mut inner = TestService::new().with_content_type("text/css");
+
+    if let Some(encoding) = encoding {
+        inner = inner.with_header("content-encoding", encoding);
+    }
+
+    let service = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(60))
+        .with_encoding_rules(&[
+            ("br", Duration::from_secs(86400)),
+            ("gzip", Duration::from_secs(3600)),
+        ])
+        .layer(inner);
+
+    test_util::oneshot(service, test_util::get("/")).await
+}
+
+#[tokio::test]
+async fn selects_max_age_by_encoding() {
+    for (encoding, expected) in [
+        (Some("br"), "public, max-age=86400"),
+        (Some("gzip"), "public, max-age=3600"),
+        (Some("GZIP"), "public, max-age=3600"),
+        (Some("zstd"), "public, max-age=60"),
+        (Some("identity"), "public, max-age=60"),
+        (None, "public, max-age=60"),
+    ] {
+        let response = respond(encoding).await;
+        assert_eq!(
+            test_util::cache_control(&response),
+            Some(expected),
+            "{encoding:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn varies_on_accept_encoding() {
+    for encoding in [Some("br"), Some("gzip"), None] {
+        let response = respond(encoding).await;
+        assert_eq!(response.headers()[VARY], "accept-encoding", "{encoding:?}");
+    }
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "conditional")]
+
+use axum_cc::{
+    assert_cache_control,
+    etag::{EtagLayer, EtagStrength},
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use axum_core::body::Body;
+use http::{
+    header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    Request, StatusCode,
+};
+use tower_layer::Layer;
+
+fn inner() -> TestService {
+    TestService::new()
+        .with_content_type("text/css")
+        .with_body("body { color: red }")
+}
+
+fn if_none_match(tag: &str) -> Request<Body> {
+    Request::get("/")
+        .header(IF_NONE_MATCH, tag)
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn etag(strength: EtagStrength) -> String {
+    let service = EtagLayer::new().with_strength(strength).layer(inner());
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    response.headers()[ETAG].to_str().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn strong_by_default() {
+    let service = EtagLayer::new().layer(inner());
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    let tag = response.headers()[ETAG].to_str().unwrap();
+
+    assert!(tag.starts_with('"') && tag.ends_with('"'), "{tag}");
+    assert_eq!(tag, etag(EtagStrength::Strong).await);
+}
+
+#[tokio::test]
+async fn weak_tags_are_prefixed() {
+    let strong = etag(EtagStrength::Strong).await;
+    let weak = etag(EtagStrength::Weak).await;
+
+    assert_eq!(weak, format!("W/{strong}"));
+}
+
+#[tokio::test]
+async fn matching_tags_get_not_modified() {
+    for strength in [EtagStrength::Strong, EtagStrength::Weak] {
+        let tag = etag(strength).await;
+
+        let service = EtagLayer::new()
+            .with_strength(strength)
+            .layer(CacheControlLayer::new().layer(inner()));
+        let response = test_util::oneshot(service, if_none_match(&tag)).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], tag.as_str());
+        assert!(!response.headers().contains_key(CONTENT_TYPE));
+        assert_cache_control!(response, "public, max-age=31536000");
+    }
+}
+
+#[tokio::test]
+async fn weak_layers_compare_tags_weakly() {
+    let strong = etag(EtagStrength::Strong).await;
+    let weak = etag(EtagStrength::Weak).await;
+
+    // A weak request tag matches a strong response tag, and the other way
+    // around.
+    for tag in [
+        weak.clone(),
+        strong.clone(),
+        format!("\"other\", {strong}"),
+        "*".to_owned(),
+    ] {
+        let service = EtagLayer::new()
+            .with_strength(EtagStrength::Weak)
+            .layer(inner());
+        let response = test_util::oneshot(service, if_none_match(&tag)).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED, "{tag}");
+    }
+}
+
+#[tokio::test]
+async fn strong_layers_compare_tags_strongly() {
+    let strong = etag(EtagStrength::Strong).await;
+
+    for (tag, status) in [
+        (strong.clone(), StatusCode::NOT_MODIFIED),
+        (format!("\"other\", {strong}"), StatusCode::NOT_MODIFIED),
+        ("*".to_owned(), StatusCode::NOT_MODIFIED),
+        (format!("W/{strong}"), StatusCode::OK),
+    ] {
+        let service = EtagLayer::new().layer(inner());
+        let response = test_util::oneshot(service, if_none_match(&tag)).await;
+
+        assert_eq!(response.status(), status, "{tag}");
+    }
+}
+
+#[tokio::test]
+async fn strong_layers_never_match_weak_handler_tags() {
+    let inner = inner().with_header("etag", "W/\"v1\"");
+
+    for (strength, status) in [
+        (EtagStrength::Strong, StatusCode::OK),
+        (EtagStrength::Weak, StatusCode::NOT_MODIFIED),
+    ] {
+        let service = EtagLayer::new()
+            .with_strength(strength)
+            .layer(inner.clone());
+        let response = test_util::oneshot(service, if_none_match("W/\"v1\"")).await;
+
+        assert_eq!(response.status(), status, "{strength:?}");
+    }
+}
+
+#[tokio::test]
+async fn cache_control_layer_weakens_handler_tags() {
+    let inner = inner().with_header("etag", "\"v1\"");
+
+    for (strength, tag) in [
+        (EtagStrength::Strong, "\"v1\""),
+        (EtagStrength::Weak, "W/\"v1\""),
+    ] {
+        let service = CacheControlLayer::new()
+            .with_etag_strength(strength)
+            .layer(inner.clone());
+        let response = test_util::oneshot(service, test_util::get("/")).await;
+
+        assert_eq!(response.headers()[ETAG], tag, "{strength:?}");
+    }
+}
+
+#[tokio::test]
+async fn cache_control_layer_keeps_weak_tags_weak() {
+    let service = CacheControlLayer::new()
+        .with_etag_strength(EtagStrength::Weak)
+        .layer(inner().with_header("etag", "W/\"v1\""));
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_eq!(response.headers()[ETAG], "W/\"v1\"");
+}
+
+#[tokio::test]
+async fn different_tags_get_the_full_response() {
+    let service = EtagLayer::new().layer(inner());
+    let response = test_util::oneshot(service, if_none_match("\"other\"")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key(ETAG));
+}
@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime};
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Expiry, MimeType,
+};
+use tower_layer::Layer;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+async fn fetch(layer: CacheControlLayer, service: TestService) -> http::Response<String> {
+    test_util::oneshot(layer.layer(service), test_util::get("/")).await
+}
+
+fn css() -> TestService {
+    TestService::new().with_content_type("text/css")
+}
+
+fn max_age(response: &http::Response<String>) -> u64 {
+    let value = test_util::cache_control(response).unwrap();
+    value
+        .strip_prefix("public, max-age=")
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn at_counts_down_to_the_deadline() {
+    let layer = CacheControlLayer::new().with_expiry(Expiry::At(SystemTime::now() + HOUR));
+
+    let max_age = max_age(&fetch(layer, css()).await);
+    assert!((3590..=3600).contains(&max_age), "{max_age}");
+}
+
+#[tokio::test]
+async fn passed_deadlines_emit_zero() {
+    let layer = CacheControlLayer::new().with_expiry(Expiry::At(SystemTime::now() - HOUR));
+
+    assert_cache_control!(fetch(layer, css()).await, "public, max-age=0");
+}
+
+#[tokio::test]
+async fn daily_deadlines_are_at_most_a_day_away() {
+    let layer = CacheControlLayer::new().with_expiry(Expiry::DailyAt(2 * HOUR));
+
+    let max_age = max_age(&fetch(layer, css()).await);
+    assert!((1..=DAY.as_secs()).contains(&max_age), "{max_age}");
+}
+
+#[tokio::test]
+async fn after_modified_counts_from_last_modified() {
+    let layer = CacheControlLayer::new().with_expiry(Expiry::AfterModified(DAY));
+    let modified = httpdate::fmt_http_date(SystemTime::now() - HOUR);
+
+    let max_age =
+        max_age(&fetch(layer.clone(), css().with_header("last-modified", &modified)).await);
+    assert!((82_790..=82_800).contains(&max_age), "{max_age}");
+
+    // Without a `Last-Modified`, the policy's `max-age` applies.
+    assert_cache_control!(fetch(layer, css()).await, "public, max-age=31536000");
+}
+
+#[tokio::test]
+async fn policies_without_max_age_are_unaffected() {
+    let layer = CacheControlLayer::new()
+        .with_policy(MimeType::HTML, CacheControlDirectives::new().no_store())
+        .with_expiry(Expiry::At(SystemTime::now() + HOUR));
+
+    let response = fetch(layer, TestService::new().with_content_type("text/html")).await;
+    assert_cache_control!(response, "no-store");
+}
@@ -0,0 +1,48 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, FetchSite,
+};
+use http::HeaderValue;
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new().with_fetch_site_rules(&[
+        (FetchSite::CrossSite, "no-store"),
+        (FetchSite::SameSite, "private, max-age=60"),
+    ])
+}
+
+async fn fetch(content_type: &str, site: Option<&'static str>) -> http::Response<String> {
+    let service = layer().layer(TestService::new().with_content_type(content_type));
+
+    let mut request = test_util::get("/");
+    if let Some(site) = site {
+        request
+            .headers_mut()
+            .insert("sec-fetch-site", HeaderValue::from_static(site));
+    }
+
+    test_util::oneshot(service, request).await
+}
+
+#[tokio::test]
+async fn listed_contexts_get_their_directives() {
+    assert_cache_control!(fetch("text/css", Some("cross-site")).await, "no-store");
+    assert_cache_control!(
+        fetch("text/css", Some("same-site")).await,
+        "private, max-age=60"
+    );
+}
+
+#[tokio::test]
+async fn other_contexts_keep_the_policy() {
+    for site in [Some("same-origin"), Some("none"), Some("bogus"), None] {
+        assert_cache_control!(fetch("text/css", site).await, "public, max-age=31536000");
+    }
+}
+
+#[tokio::test]
+async fn unmatched_responses_are_left_alone() {
+    assert_cache_control!(fetch("text/html", Some("cross-site")).await, None);
+}
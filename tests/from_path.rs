@@ -0,0 +1,30 @@
+use axum_cc::MimeType;
+
+#[test]
+fn known_extensions() {
+    assert_eq!(MimeType::from_path("style.css"), Some(MimeType::CSS));
+    assert_eq!(
+        MimeType::from_path("/assets/app.min.js"),
+        Some(MimeType::JS)
+    );
+    assert_eq!(MimeType::from_path("assets/Logo.PNG"), Some(MimeType::PNG));
+    assert_eq!(
+        MimeType::from_path(r"assets\font.woff2"),
+        Some(MimeType::WOFF2)
+    );
+}
+
+#[test]
+fn queries_and_fragments_are_ignored() {
+    assert_eq!(MimeType::from_path("/app.js?v=3"), Some(MimeType::JS));
+    assert_eq!(MimeType::from_path("/icon.svg#logo"), Some(MimeType::SVG));
+    assert_eq!(MimeType::from_path("/download?file=app.js"), None);
+}
+
+#[test]
+fn paths_without_a_known_extension() {
+    assert_eq!(MimeType::from_path("Makefile"), None);
+    assert_eq!(MimeType::from_path(".env"), None);
+    assert_eq!(MimeType::from_path("/assets/"), None);
+    assert_eq!(MimeType::from_path("archive.v1.x7z"), None);
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, Matcher, OverwriteBehavior,
+};
+use http::StatusCode;
+use tower_layer::Layer;
+
+#[tokio::test]
+async fn never_caches_switching_protocols() {
+    let service = CacheControlLayer::new().layer(
+        TestService::new()
+            .with_status(StatusCode::SWITCHING_PROTOCOLS)
+            .with_content_type("text/css"),
+    );
+
+    let response = test_util::oneshot(service, test_util::get("/ws")).await;
+
+    assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+    assert_cache_control!(response, None);
+}
+
+#[tokio::test]
+async fn never_caches_informational_responses() {
+    let layer = CacheControlLayer::new()
+        .rule(Matcher::any(), Duration::from_secs(60))
+        .with_default_policy(Duration::from_secs(60))
+        .with_overwrite_behavior(OverwriteBehavior::Always);
+
+    for status in [100, 101, 102, 103] {
+        let service = layer.clone().layer(
+            TestService::new()
+                .with_status(StatusCode::from_u16(status).unwrap())
+                .with_content_type("text/css"),
+        );
+
+        let response = test_util::oneshot(service, test_util::get("/")).await;
+        assert_cache_control!(response, None);
+    }
+}
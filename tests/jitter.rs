@@ -0,0 +1,55 @@
+use std::{collections::HashSet, time::Duration};
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+async fn max_ages(layer: CacheControlLayer, n: usize) -> Vec<u64> {
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+    let mut max_ages = Vec::new();
+
+    for _ in 0..n {
+        let response = test_util::oneshot(service.clone(), test_util::get("/")).await;
+        let value = test_util::cache_control(&response).unwrap();
+        let max_age = value.strip_prefix("public, max-age=").unwrap();
+        max_ages.push(max_age.parse().unwrap());
+    }
+
+    max_ages
+}
+
+#[tokio::test]
+async fn jitter_stays_within_the_window() {
+    let layer = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(3600))
+        .with_max_age_jitter(Duration::from_secs(600));
+
+    let max_ages = max_ages(layer, 200).await;
+    assert!(max_ages
+        .iter()
+        .all(|max_age| (3000..=3600).contains(max_age)));
+
+    // With 601 possible values, 200 identical picks mean there is no jitter.
+    assert!(max_ages.iter().collect::<HashSet<_>>().len() > 1);
+}
+
+#[tokio::test]
+async fn jitter_never_goes_below_zero() {
+    let layer = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(10))
+        .with_max_age_jitter(Duration::from_secs(600));
+
+    let max_ages = max_ages(layer, 100).await;
+    assert!(max_ages.iter().all(|max_age| *max_age <= 10));
+}
+
+#[tokio::test]
+async fn zero_jitter_is_no_jitter() {
+    let layer = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(3600))
+        .with_max_age_jitter(Duration::ZERO);
+
+    assert_eq!(max_ages(layer, 20).await, vec![3600; 20]);
+}
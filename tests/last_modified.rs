@@ -0,0 +1,120 @@
+#![cfg(feature = "conditional")]
+
+use std::time::{Duration, SystemTime};
+
+use axum::{
+    body::Body,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use axum_cc::{
+    last_modified::{LastModified, LastModifiedLayer},
+    test_util::{self, TestService},
+};
+use http::{header::LAST_MODIFIED, HeaderValue, Method, Request, StatusCode};
+use tower_layer::Layer;
+
+/// A whole number of seconds, since HTTP dates have no fractions.
+fn modified() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+}
+
+fn date(time: SystemTime) -> String {
+    httpdate::fmt_http_date(time)
+}
+
+fn request(method: Method, if_modified_since: Option<SystemTime>) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri("/")
+        .body(Body::empty())
+        .unwrap();
+
+    if let Some(since) = if_modified_since {
+        request.headers_mut().insert(
+            "if-modified-since",
+            HeaderValue::from_str(&date(since)).unwrap(),
+        );
+    }
+
+    request
+}
+
+async fn fetch(request: Request<Body>) -> Response {
+    async fn handler() -> Response {
+        let mut response = "hello".into_response();
+        response.extensions_mut().insert(LastModified(modified()));
+        response
+    }
+
+    let app = Router::new()
+        .route("/", get(handler))
+        .layer(LastModifiedLayer::new());
+
+    test_util::oneshot(app, request).await
+}
+
+#[tokio::test]
+async fn the_extension_sets_last_modified() {
+    let response = fetch(request(Method::GET, None)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[LAST_MODIFIED], date(modified()));
+}
+
+#[tokio::test]
+async fn unchanged_responses_are_not_modified() {
+    for since in [modified(), modified() + Duration::from_secs(60)] {
+        let response = fetch(request(Method::GET, Some(since))).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[LAST_MODIFIED], date(modified()));
+    }
+}
+
+#[tokio::test]
+async fn changed_responses_are_sent_in_full() {
+    let since = modified() - Duration::from_secs(60);
+
+    let response = fetch(request(Method::GET, Some(since))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn if_none_match_takes_precedence() {
+    let mut request = request(Method::GET, Some(modified()));
+    request
+        .headers_mut()
+        .insert("if-none-match", HeaderValue::from_static("\"abc\""));
+
+    let response = fetch(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn existing_headers_are_used() {
+    let service = LastModifiedLayer::new()
+        .layer(TestService::new().with_header("last-modified", &date(modified())));
+
+    let response = test_util::oneshot(service, request(Method::GET, Some(modified()))).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn a_custom_source() {
+    let service = LastModifiedLayer::new()
+        .with_source(|_| Some(modified()))
+        .layer(TestService::new());
+
+    let response = test_util::oneshot(service, request(Method::HEAD, Some(modified()))).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn only_safe_methods_are_answered() {
+    let service = LastModifiedLayer::new()
+        .with_source(|_| Some(modified()))
+        .layer(TestService::new());
+
+    let response = test_util::oneshot(service, request(Method::POST, Some(modified()))).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "macros")]
+
+use axum::{extract::Path, routing::get, Router};
+use axum_cc::{assert_cache_control, cache_control, test_util, CacheControlLayer};
+
+#[cache_control(public, max_age = "1d", immutable)]
+async fn logo() -> &'static str {
+    "<svg></svg>"
+}
+
+#[cache_control(private, max_age = 60, stale_while_revalidate = 30)]
+async fn user(Path(id): Path<u32>) -> String {
+    format!("user {id}")
+}
+
+#[cache_control(no_store)]
+async fn nothing() {}
+
+fn app() -> Router {
+    Router::new()
+        .route("/logo", get(logo))
+        .route("/users/:id", get(user))
+        .route("/nothing", get(nothing))
+}
+
+async fn fetch(app: Router, uri: &str) -> http::Response<axum::body::Body> {
+    test_util::oneshot(app, test_util::get(uri)).await
+}
+
+#[tokio::test]
+async fn handlers_get_their_directives() {
+    assert_cache_control!(
+        fetch(app(), "/logo").await,
+        "public, max-age=86400, immutable"
+    );
+    assert_cache_control!(fetch(app(), "/nothing").await, "no-store");
+}
+
+#[tokio::test]
+async fn handlers_keep_their_extractors() {
+    let response = fetch(app(), "/users/42").await;
+    assert_cache_control!(response, "private, max-age=60, stale-while-revalidate=30");
+}
+
+#[tokio::test]
+async fn a_layer_keeps_the_handler_directives() {
+    let app = app().layer(CacheControlLayer::new());
+    assert_cache_control!(
+        fetch(app, "/logo").await,
+        "public, max-age=86400, immutable"
+    );
+}
@@ -0,0 +1,28 @@
+use std::{num::NonZeroU64, time::Duration};
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+#[tokio::test]
+async fn nonzero_max_age() {
+    let layer = CacheControlLayer::new().with_max_age_nonzero(NonZeroU64::new(600).unwrap());
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=600");
+}
+
+#[tokio::test]
+async fn nonzero_max_age_replaces_zero() {
+    let layer = CacheControlLayer::new()
+        .with_max_age(Duration::ZERO)
+        .with_max_age_nonzero(NonZeroU64::MIN);
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=1");
+}
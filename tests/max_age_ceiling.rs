@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher,
+};
+use tower_layer::Layer;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+async fn cache_control(directives: CacheControlDirectives) -> Option<String> {
+    let service = CacheControlLayer::new()
+        .with_directives(directives)
+        .with_max_age_ceiling(secs(3600))
+        .layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn clamps_max_age() {
+    let directives = CacheControlDirectives::new()
+        .public()
+        .with_max_age(secs(86400));
+
+    assert_eq!(
+        cache_control(directives).await.as_deref(),
+        Some("public, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn clamps_s_maxage() {
+    let directives = CacheControlDirectives::new()
+        .public()
+        .with_max_age(secs(60))
+        .with_s_maxage(secs(31536000));
+
+    assert_eq!(
+        cache_control(directives).await.as_deref(),
+        Some("public, max-age=60, s-maxage=3600")
+    );
+}
+
+#[tokio::test]
+async fn leaves_lifetimes_under_the_ceiling() {
+    let directives = CacheControlDirectives::new()
+        .public()
+        .with_max_age(secs(3600))
+        .with_s_maxage(secs(600));
+
+    assert_eq!(
+        cache_control(directives).await.as_deref(),
+        Some("public, max-age=3600, s-maxage=600")
+    );
+}
+
+#[tokio::test]
+async fn clamps_rules() {
+    let service = CacheControlLayer::default()
+        .rule(Matcher::any(), secs(31536000))
+        .with_max_age_ceiling(secs(3600))
+        .layer(TestService::new());
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(
+        test_util::cache_control(&response),
+        Some("public, max-age=3600")
+    );
+}
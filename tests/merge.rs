@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, OverwriteBehavior,
+};
+use tower_layer::Layer;
+
+/// Merge `layer` into a CSS response that already has `existing`.
+async fn merge(existing: &str, layer: CacheControlDirectives) -> Option<String> {
+    let service = CacheControlLayer::new()
+        .with_directives(layer)
+        .with_overwrite_behavior(OverwriteBehavior::Merge)
+        .layer(
+            TestService::new()
+                .with_content_type("text/css")
+                .with_header("cache-control", existing),
+        );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+fn hour() -> Duration {
+    Duration::from_secs(3600)
+}
+
+#[tokio::test]
+async fn adds_complementary_directives() {
+    let layer = CacheControlDirectives::new().with_max_age(hour());
+
+    assert_eq!(
+        merge("no-transform", layer).await.as_deref(),
+        Some("no-transform, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn layer_wins_on_shared_directives() {
+    let layer = CacheControlDirectives::new().public().with_max_age(hour());
+
+    assert_eq!(
+        merge("max-age=60, must-revalidate", layer).await.as_deref(),
+        Some("must-revalidate, public, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn existing_private_drops_public_and_s_maxage() {
+    let layer = CacheControlDirectives::new()
+        .public()
+        .with_max_age(hour())
+        .with_s_maxage(hour());
+
+    assert_eq!(
+        merge("private, max-age=60", layer).await.as_deref(),
+        Some("private, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn layer_private_drops_existing_public() {
+    let layer = CacheControlDirectives::new().private().with_max_age(hour());
+
+    assert_eq!(
+        merge("public, s-maxage=600", layer).await.as_deref(),
+        Some("private, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn existing_no_cache_drops_immutable() {
+    let layer = CacheControlDirectives::new()
+        .public()
+        .with_max_age(hour())
+        .immutable();
+
+    assert_eq!(
+        merge("no-cache", layer).await.as_deref(),
+        Some("no-cache, public, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn existing_no_store_is_kept() {
+    let layer = CacheControlDirectives::new().public().with_max_age(hour());
+
+    assert_eq!(merge("no-store", layer).await.as_deref(), Some("no-store"));
+}
+
+#[tokio::test]
+async fn layer_no_store_drops_storage_directives() {
+    let layer = CacheControlDirectives::new().no_store();
+
+    assert_eq!(
+        merge(
+            "public, max-age=60, stale-if-error=300, no-transform",
+            layer
+        )
+        .await
+        .as_deref(),
+        Some("no-transform, no-store")
+    );
+}
+
+#[tokio::test]
+async fn merged_values_are_valid() {
+    let existing = [
+        "private",
+        "public",
+        "no-cache",
+        "max-age=60",
+        "private, no-cache",
+    ];
+    let layers = [
+        CacheControlDirectives::new().public().with_s_maxage(hour()),
+        CacheControlDirectives::new().private().with_max_age(hour()),
+        CacheControlDirectives::new()
+            .public()
+            .with_max_age(hour())
+            .immutable(),
+        CacheControlDirectives::new().no_store(),
+    ];
+
+    for existing in existing {
+        for layer in &layers {
+            let merged = merge(existing, layer.clone()).await.unwrap();
+            let directives: Vec<_> = merged.split(", ").collect();
+
+            let conflict = (directives.contains(&"public") && directives.contains(&"private"))
+                || (directives.contains(&"no-store")
+                    && directives.iter().any(|d| d.starts_with("max-age")))
+                || (directives.contains(&"no-cache") && directives.contains(&"immutable"))
+                || (directives.contains(&"private")
+                    && directives.iter().any(|d| d.starts_with("s-maxage")));
+
+            assert!(!conflict, "merging {layer} into {existing} gave {merged}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn matches_directives_case_insensitively() {
+    let layer = CacheControlDirectives::new().with_max_age(hour());
+
+    assert_eq!(
+        merge("Max-Age=60, No-Transform", layer).await.as_deref(),
+        Some("No-Transform, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn keeps_quoted_values_intact() {
+    let layer = CacheControlDirectives::new().with_max_age(hour());
+
+    assert_eq!(
+        merge(r#"private="set-cookie, x-user", max-age=60"#, layer)
+            .await
+            .as_deref(),
+        Some(r#"private="set-cookie, x-user", max-age=3600"#)
+    );
+}
+
+#[tokio::test]
+async fn keeps_unknown_extensions() {
+    let layer = CacheControlDirectives::new().public().with_max_age(hour());
+
+    assert_eq!(
+        merge("community=\"UCI\", max-age=60", layer)
+            .await
+            .as_deref(),
+        Some("community=\"UCI\", public, max-age=3600")
+    );
+}
+
+#[tokio::test]
+async fn only_merges_in_merge_mode() {
+    let service = |behavior| {
+        CacheControlLayer::new()
+            .with_overwrite_behavior(behavior)
+            .layer(
+                TestService::new()
+                    .with_content_type("text/css")
+                    .with_header("cache-control", "private"),
+            )
+    };
+
+    let response = test_util::oneshot(
+        service(OverwriteBehavior::SkipIfPresent),
+        test_util::get("/"),
+    )
+    .await;
+    assert_eq!(test_util::cache_control(&response), Some("private"));
+
+    let response =
+        test_util::oneshot(service(OverwriteBehavior::Always), test_util::get("/")).await;
+    assert_eq!(
+        test_util::cache_control(&response),
+        Some("public, max-age=31536000")
+    );
+
+    let response = test_util::oneshot(service(OverwriteBehavior::Merge), test_util::get("/")).await;
+    assert_eq!(
+        test_util::cache_control(&response),
+        Some("private, max-age=31536000")
+    );
+}
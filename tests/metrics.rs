@@ -0,0 +1,142 @@
+#![cfg(feature = "metrics")]
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer, Matcher, MimeType,
+};
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+use tower_layer::Layer;
+
+/// Counts increments per metric, keyed by name and labels.
+#[derive(Default)]
+struct Counters(Mutex<BTreeMap<String, Arc<Count>>>);
+
+#[derive(Default)]
+struct Count(AtomicU64);
+
+impl CounterFn for Count {
+    fn increment(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+}
+
+impl Counters {
+    fn get(&self) -> BTreeMap<String, u64> {
+        let counters = self.0.lock().unwrap();
+        counters
+            .iter()
+            .map(|(key, count)| (key.clone(), count.0.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+impl Recorder for Counters {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let labels: Vec<_> = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect();
+        let name = format!("{}{{{}}}", key.name(), labels.join(","));
+
+        let count = self.0.lock().unwrap().entry(name).or_default().clone();
+        Counter::from_arc(count)
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+/// Send a request for each content type through `layer`, returning the
+/// counters recorded.
+fn record(layer: CacheControlLayer, content_types: &[&str]) -> BTreeMap<String, u64> {
+    let counters = Counters::default();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    metrics::with_local_recorder(&counters, || {
+        for content_type in content_types {
+            let service = layer
+                .clone()
+                .layer(TestService::new().with_content_type(content_type));
+            runtime.block_on(test_util::oneshot(service, test_util::get("/")));
+        }
+    });
+
+    counters.get()
+}
+
+#[test]
+fn counts_applied_by_mime_and_rule() {
+    let layer = CacheControlLayer::new().named_rule(
+        "styles",
+        Matcher::mime(MimeType::CSS),
+        std::time::Duration::from_secs(60),
+    );
+
+    let counters = record(layer, &["text/css", "text/css", "application/javascript"]);
+
+    assert_eq!(
+        counters,
+        BTreeMap::from([
+            (
+                "cache_control_applied_total{mime=text/css,rule=styles}".to_owned(),
+                2
+            ),
+            (
+                "cache_control_applied_total{mime=application/javascript,rule=mime-types}"
+                    .to_owned(),
+                1
+            ),
+        ])
+    );
+}
+
+#[test]
+fn counts_skipped_by_reason() {
+    let counters = record(
+        CacheControlLayer::new(),
+        &[
+            "application/x-unknown",
+            "not a type",
+            "application/x-unknown",
+        ],
+    );
+
+    assert_eq!(
+        counters,
+        BTreeMap::from([
+            (
+                "cache_control_skipped_total{reason=unknown_content_type}".to_owned(),
+                1
+            ),
+            (
+                "cache_control_skipped_total{reason=unmatched_mime}".to_owned(),
+                2
+            ),
+        ])
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "axum")]
+
+use axum::{
+    body::Body,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use axum_cc::{
+    assert_cache_control, cache_control, test_util, CacheControlDirectives, CacheControlLayer,
+    SharedCacheControlLayer,
+};
+use http::{header::CONTENT_TYPE, Method, Request};
+
+async fn css() -> Response {
+    ([(CONTENT_TYPE, "text/css")], "body {}").into_response()
+}
+
+fn app(policy: SharedCacheControlLayer) -> Router {
+    Router::new()
+        .route("/style.css", get(css).post(css))
+        .route("/", get(|| async { "hello" }))
+        .layer(middleware::from_fn_with_state(policy, cache_control))
+}
+
+fn policy() -> SharedCacheControlLayer {
+    SharedCacheControlLayer::new(CacheControlLayer::new())
+}
+
+#[tokio::test]
+async fn matched_responses_get_the_policy() {
+    let response = test_util::oneshot(app(policy()), test_util::get("/style.css")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+}
+
+#[tokio::test]
+async fn unmatched_responses_are_left_alone() {
+    let response = test_util::oneshot(app(policy()), test_util::get("/")).await;
+    assert_cache_control!(response, None);
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/style.css")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = test_util::oneshot(app(policy()), request).await;
+    assert_cache_control!(response, None);
+}
+
+#[tokio::test]
+async fn the_policy_can_be_reloaded() {
+    let policy = policy();
+    let app = app(policy.clone());
+
+    policy
+        .reload(CacheControlLayer::new().with_directives(CacheControlDirectives::new().no_store()));
+
+    let response = test_util::oneshot(app, test_util::get("/style.css")).await;
+    assert_cache_control!(response, "no-store");
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "mime")]
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer, MimeType,
+};
+use tower_layer::Layer;
+
+#[test]
+fn conversions() {
+    assert_eq!(MimeType::from(mime::TEXT_CSS), MimeType::CSS);
+    assert_eq!(MimeType::from(mime::TEXT_CSS_UTF_8), MimeType::CSS);
+    assert_eq!(mime::Mime::from(MimeType::PNG), mime::IMAGE_PNG);
+}
+
+#[tokio::test]
+async fn with_mimes() {
+    let layer = CacheControlLayer::new().with_mimes(&[mime::TEXT_CSS, mime::IMAGE_PNG]);
+
+    for (content_type, expected) in [
+        ("text/css", Some("public, max-age=31536000")),
+        ("image/png", Some("public, max-age=31536000")),
+        ("text/javascript", None),
+    ] {
+        let service = layer
+            .clone()
+            .layer(TestService::new().with_content_type(content_type));
+
+        let response = test_util::oneshot(service, test_util::get("/")).await;
+        assert_eq!(
+            test_util::cache_control(&response),
+            expected,
+            "{content_type}"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, Matcher, MimeType,
+};
+use tower_layer::Layer;
+
+async fn fetch(layer: &CacheControlLayer, content_type: &str) -> http::Response<String> {
+    let service = layer
+        .clone()
+        .layer(TestService::new().with_content_type(content_type));
+    test_util::oneshot(service, test_util::get("/")).await
+}
+
+#[test]
+fn families() {
+    assert!(MimeType::AVIF.matches("image/*"));
+    assert!(MimeType::PNG.matches("image"));
+    assert!(MimeType::WOFF2.matches("font/*"));
+    assert!(MimeType::CSS.matches("text/css"));
+    assert!(!MimeType::CSS.matches("text/html"));
+    assert!(!MimeType::CSS.matches("image/*"));
+    assert_eq!(MimeType::SVG.top_level(), "image");
+}
+
+#[tokio::test]
+async fn rules_match_whole_families() {
+    let layer = CacheControlLayer::default()
+        .rule(Matcher::mime_family("image/*"), Duration::from_secs(60))
+        .rule(Matcher::mime_family("font"), Duration::from_secs(600));
+
+    for (content_type, expected) in [
+        ("image/png", Some("public, max-age=60")),
+        ("image/svg+xml", Some("public, max-age=60")),
+        ("image/x-unknown", Some("public, max-age=60")),
+        ("font/woff2", Some("public, max-age=600")),
+        ("text/css", None),
+    ] {
+        let response = fetch(&layer, content_type).await;
+        assert_eq!(
+            test_util::cache_control(&response),
+            expected,
+            "{content_type}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn families_combine_with_other_conditions() {
+    let layer = CacheControlLayer::default().rule(
+        Matcher::path_prefix("/avatars").with_mime_family("image/*"),
+        Duration::from_secs(60),
+    );
+
+    let service = layer
+        .clone()
+        .layer(TestService::new().with_content_type("image/png"));
+    let response = test_util::oneshot(service, test_util::get("/avatars/1.png")).await;
+    assert_cache_control!(response, "public, max-age=60");
+
+    assert_cache_control!(fetch(&layer, "image/png").await, None);
+}
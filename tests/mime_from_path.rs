@@ -0,0 +1,49 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+async fn fetch(
+    layer: CacheControlLayer,
+    service: TestService,
+    uri: &str,
+) -> http::Response<String> {
+    test_util::oneshot(layer.layer(service), test_util::get(uri)).await
+}
+
+#[tokio::test]
+async fn the_path_stands_in_for_a_missing_content_type() {
+    let layer = CacheControlLayer::new().with_mime_from_path();
+
+    assert_cache_control!(
+        fetch(layer.clone(), TestService::new(), "/app.js?v=3").await,
+        "public, max-age=31536000"
+    );
+    assert_cache_control!(fetch(layer, TestService::new(), "/index.html").await, None);
+}
+
+#[tokio::test]
+async fn the_content_type_takes_precedence() {
+    let layer = CacheControlLayer::new().with_mime_from_path();
+    let service = TestService::new().with_content_type("text/html");
+
+    assert_cache_control!(fetch(layer, service, "/app.js").await, None);
+}
+
+#[tokio::test]
+async fn malformed_content_types_are_not_replaced() {
+    let layer = CacheControlLayer::new().with_mime_from_path();
+    let service = TestService::new().with_content_type("nonsense");
+
+    assert_cache_control!(fetch(layer, service, "/app.js").await, None);
+}
+
+#[tokio::test]
+async fn the_path_is_ignored_by_default() {
+    assert_cache_control!(
+        fetch(CacheControlLayer::new(), TestService::new(), "/app.js").await,
+        None
+    );
+}
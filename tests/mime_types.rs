@@ -0,0 +1,34 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, MimeType,
+};
+use tower_layer::Layer;
+
+#[test]
+fn documents_and_data() {
+    assert_eq!(MimeType::from_extension("pdf"), MimeType::PDF);
+    assert_eq!(MimeType::from_extension("csv"), MimeType::CSV);
+    assert_eq!(MimeType::PDF.as_str(), "application/pdf");
+    assert_eq!(MimeType::CSV.as_str(), "text/csv");
+}
+
+#[test]
+fn only_csv_is_compressible() {
+    assert!(MimeType::CSV.is_compressible());
+    assert!(!MimeType::PDF.is_compressible());
+}
+
+#[tokio::test]
+async fn documents_can_be_matched() {
+    let layer = CacheControlLayer::new().with_mime_types(&[MimeType::PDF, MimeType::CSV]);
+
+    for content_type in ["application/pdf", "text/csv; charset=utf-8"] {
+        let service = layer
+            .clone()
+            .layer(TestService::new().with_content_type(content_type));
+
+        let response = test_util::oneshot(service, test_util::get("/")).await;
+        assert_cache_control!(response, "public, max-age=31536000");
+    }
+}
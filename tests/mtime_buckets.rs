@@ -0,0 +1,49 @@
+use std::time::{Duration, SystemTime};
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new().with_mtime_buckets(&[(DAY, Duration::from_secs(60)), (7 * DAY, HOUR)])
+}
+
+async fn modified(last_modified: Option<&str>) -> http::Response<String> {
+    let mut service = TestService::new().with_content_type("text/css");
+    if let Some(last_modified) = last_modified {
+        service = service.with_header("last-modified", last_modified);
+    }
+
+    test_util::oneshot(layer().layer(service), test_util::get("/")).await
+}
+
+async fn modified_ago(ago: Duration) -> http::Response<String> {
+    let date = httpdate::fmt_http_date(SystemTime::now() - ago);
+    modified(Some(&date)).await
+}
+
+#[tokio::test]
+async fn the_first_matching_bucket_wins() {
+    assert_cache_control!(modified_ago(HOUR).await, "public, max-age=60");
+    assert_cache_control!(modified_ago(3 * DAY).await, "public, max-age=3600");
+}
+
+#[tokio::test]
+async fn older_responses_keep_the_policy() {
+    assert_cache_control!(modified_ago(30 * DAY).await, "public, max-age=31536000");
+}
+
+#[tokio::test]
+async fn missing_or_invalid_dates_keep_the_policy() {
+    assert_cache_control!(modified(None).await, "public, max-age=31536000");
+    assert_cache_control!(
+        modified(Some("yesterday")).await,
+        "public, max-age=31536000"
+    );
+}
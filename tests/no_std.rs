@@ -0,0 +1,52 @@
+//! The `no_std` core of the crate, used through `core` and `alloc` only.
+//!
+//! This checks the API without `std`'s prelude; building the crate itself
+//! without `std` needs a `no_std` target:
+//!
+//! ```sh
+//! cargo build --no-default-features --features alloc --target thumbv7em-none-eabihf
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::ToString;
+use core::time::Duration;
+
+use axum_cc::{parse_duration, CacheControlDirectives, CacheControlError, FetchSite, MimeType};
+
+#[test]
+fn parses_mime_types() {
+    assert_eq!("text/css; charset=utf-8".parse(), Ok(MimeType::CSS));
+    assert_eq!(MimeType::JS.as_str(), "application/javascript");
+    assert!(MimeType::HTML.is_compressible());
+    assert_eq!(
+        "not a type".parse::<MimeType>(),
+        Err(CacheControlError::InvalidMimeType)
+    );
+}
+
+#[test]
+fn serializes_directives() {
+    let directives = CacheControlDirectives::new()
+        .public()
+        .with_max_age(Duration::from_secs(60))
+        .with_stale_while_revalidate(Duration::from_secs(30));
+
+    assert_eq!(
+        directives.to_string(),
+        "public, max-age=60, stale-while-revalidate=30"
+    );
+    assert_eq!(directives.validate(), Ok(()));
+    assert_eq!(
+        CacheControlDirectives::new().validate(),
+        Err(CacheControlError::EmptyDirectives)
+    );
+}
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+    assert_eq!(FetchSite::CrossSite.as_str(), "cross-site");
+}
@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex};
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, CachePolicyResolver, MimeType, Outcome, ResolveFuture, SkipReason,
+};
+use http::{request, response, StatusCode};
+use tower_layer::Layer;
+
+struct Nothing;
+
+impl CachePolicyResolver for Nothing {
+    fn resolve(&self, _: &request::Parts, _: &response::Parts) -> ResolveFuture {
+        Box::pin(async { None })
+    }
+}
+
+/// A layer that records the outcomes it reports.
+fn recording(layer: CacheControlLayer) -> (CacheControlLayer, Arc<Mutex<Vec<String>>>) {
+    let outcomes = Arc::new(Mutex::new(Vec::new()));
+    let recorded = outcomes.clone();
+
+    let layer = layer.with_observer(move |outcome| {
+        recorded.lock().unwrap().push(format!("{outcome:?}"));
+    });
+
+    (layer, outcomes)
+}
+
+#[tokio::test]
+async fn reports_applied() {
+    let (layer, outcomes) = recording(CacheControlLayer::new());
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    test_util::oneshot(service, test_util::get("/")).await;
+
+    let expected = Outcome::Applied {
+        mime: Some(&MimeType::CSS),
+        rule: None,
+    };
+    assert_eq!(*outcomes.lock().unwrap(), [format!("{expected:?}")]);
+}
+
+#[tokio::test]
+async fn reports_skipped() {
+    let (layer, outcomes) = recording(CacheControlLayer::new());
+    let service = layer.layer(TestService::new().with_content_type("application/x-unknown"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_cache_control!(response, None);
+    assert_eq!(
+        *outcomes.lock().unwrap(),
+        [format!("{:?}", Outcome::Skipped(SkipReason::UnmatchedMime))]
+    );
+}
+
+#[tokio::test]
+async fn reports_skips_once_with_a_resolver() {
+    let (layer, outcomes) = recording(CacheControlLayer::new().with_resolver(Nothing));
+    let service = layer.layer(
+        TestService::new()
+            .with_status(StatusCode::SWITCHING_PROTOCOLS)
+            .with_content_type("text/css"),
+    );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_cache_control!(response, None);
+    assert_eq!(
+        *outcomes.lock().unwrap(),
+        [format!("{:?}", Outcome::Skipped(SkipReason::Informational))]
+    );
+}
+
+#[tokio::test]
+async fn reports_resolved_responses_once() {
+    let (layer, outcomes) = recording(CacheControlLayer::new().with_resolver(Nothing));
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_cache_control!(response, "public, max-age=31536000");
+    assert_eq!(outcomes.lock().unwrap().len(), 1);
+}
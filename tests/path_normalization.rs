@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer, Matcher,
+};
+use tower_layer::Layer;
+
+async fn cache_control(uri: &str) -> Option<String> {
+    let service = CacheControlLayer::default()
+        .rule(Matcher::path_prefix("/static"), Duration::from_secs(60))
+        .layer(TestService::new());
+
+    let response = test_util::oneshot(service, test_util::get(uri)).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn matches_normalized_paths() {
+    for uri in [
+        "/static",
+        "/static/app.js",
+        "//static//app.js",
+        "/static/./app.js",
+        "/x/../static/app.js",
+        "/%73tatic/app.js",
+    ] {
+        assert_eq!(
+            cache_control(uri).await.as_deref(),
+            Some("public, max-age=60"),
+            "{uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn rejects_paths_escaping_the_prefix() {
+    for uri in [
+        "/static/../admin",
+        "/static/%2e%2e/admin",
+        "/static/%2E%2E/%2E%2E/admin",
+        "/statics/app.js",
+    ] {
+        assert_eq!(cache_control(uri).await, None, "{uri}");
+    }
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "regex")]
+
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher, MimeType,
+};
+use regex::Regex;
+use tower_layer::Layer;
+
+async fn cache_control(layer: &CacheControlLayer, uri: &str) -> Option<String> {
+    let service = layer.clone().layer(TestService::new());
+    let response = test_util::oneshot(service, test_util::get(uri)).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn rules_match_paths_by_regex() {
+    let layer = CacheControlLayer::default()
+        .rule(
+            Matcher::path_regex(Regex::new(r"^/api/v\d+/").unwrap()),
+            CacheControlDirectives::new().no_store(),
+        )
+        .rule(
+            Matcher::path_regex(Regex::new(r"\.(css|js)$").unwrap()),
+            Duration::from_secs(60),
+        );
+
+    for (uri, expected) in [
+        ("/api/v1/users", Some("no-store")),
+        ("/api/v12/users?page=2", Some("no-store")),
+        ("/api/users", None),
+        ("/assets/app.js", Some("public, max-age=60")),
+        ("/assets/app.js.map", None),
+    ] {
+        assert_eq!(
+            cache_control(&layer, uri).await.as_deref(),
+            expected,
+            "{uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn regexes_see_the_normalized_path() {
+    let layer = CacheControlLayer::default().rule(
+        Matcher::path_regex(Regex::new(r"^/static/").unwrap()),
+        Duration::from_secs(60),
+    );
+
+    assert_eq!(
+        cache_control(&layer, "/api/../static/app.js")
+            .await
+            .as_deref(),
+        Some("public, max-age=60")
+    );
+}
+
+#[tokio::test]
+async fn regexes_combine_with_other_conditions() {
+    let layer = CacheControlLayer::default().rule(
+        Matcher::mime(MimeType::PNG).with_path_regex(Regex::new(r"^/avatars/").unwrap()),
+        Duration::from_secs(60),
+    );
+
+    let service = layer
+        .clone()
+        .layer(TestService::new().with_content_type("image/png"));
+    let response = test_util::oneshot(service, test_util::get("/avatars/1.png")).await;
+    assert_eq!(
+        test_util::cache_control(&response),
+        Some("public, max-age=60")
+    );
+
+    assert_eq!(cache_control(&layer, "/avatars/1.png").await, None);
+}
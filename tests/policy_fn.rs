@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher,
+};
+use http::{HeaderValue, StatusCode};
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .rule(Matcher::path_prefix("/"), Duration::from_secs(10))
+        .with_policy_fn(|req, res| {
+            if req.headers.contains_key("x-tenant") && res.status.is_server_error() {
+                Some(CacheControlDirectives::new().no_store())
+            } else if req.headers.contains_key("x-tenant") {
+                Some(
+                    CacheControlDirectives::new()
+                        .private()
+                        .with_max_age(Duration::from_secs(60)),
+                )
+            } else {
+                None
+            }
+        })
+}
+
+async fn fetch(status: StatusCode, tenant: bool) -> http::Response<String> {
+    let service = layer().layer(TestService::new().with_status(status));
+
+    let mut request = test_util::get("/");
+    if tenant {
+        request
+            .headers_mut()
+            .insert("x-tenant", HeaderValue::from_static("acme"));
+    }
+
+    test_util::oneshot(service, request).await
+}
+
+#[tokio::test]
+async fn the_function_sees_the_request_and_response() {
+    assert_cache_control!(fetch(StatusCode::OK, true).await, "private, max-age=60");
+    assert_cache_control!(fetch(StatusCode::BAD_GATEWAY, true).await, "no-store");
+}
+
+#[tokio::test]
+async fn the_function_takes_precedence_over_rules() {
+    // The rule alone would make this `public, max-age=10`.
+    assert_cache_control!(fetch(StatusCode::OK, true).await, "private, max-age=60");
+}
+
+#[tokio::test]
+async fn none_falls_back_to_the_rules() {
+    assert_cache_control!(fetch(StatusCode::OK, false).await, "public, max-age=10");
+}
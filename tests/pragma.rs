@@ -0,0 +1,53 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, MimeType,
+};
+use tower_layer::Layer;
+
+async fn fetch(layer: CacheControlLayer, service: TestService) -> http::Response<String> {
+    test_util::oneshot(layer.layer(service), test_util::get("/")).await
+}
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .with_policy(MimeType::HTML, CacheControlDirectives::new().no_cache())
+        .with_policy(MimeType::JSON, CacheControlDirectives::new().no_store())
+        .with_pragma()
+}
+
+#[tokio::test]
+async fn uncacheable_responses_get_pragma() {
+    for content_type in ["text/html", "application/json"] {
+        let response = fetch(layer(), TestService::new().with_content_type(content_type)).await;
+        assert_eq!(response.headers()["pragma"], "no-cache", "{content_type}");
+    }
+}
+
+#[tokio::test]
+async fn cacheable_responses_do_not() {
+    let response = fetch(layer(), TestService::new().with_content_type("text/css")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+    assert!(!response.headers().contains_key("pragma"));
+}
+
+#[tokio::test]
+async fn downgraded_responses_get_pragma() {
+    let service = TestService::new()
+        .with_content_type("text/css")
+        .with_header("set-cookie", "session=abc");
+
+    let response = fetch(layer(), service).await;
+    assert_cache_control!(response, "no-store");
+    assert_eq!(response.headers()["pragma"], "no-cache");
+}
+
+#[tokio::test]
+async fn pragma_is_opt_in() {
+    let layer = CacheControlLayer::new()
+        .with_policy(MimeType::HTML, CacheControlDirectives::new().no_store());
+
+    let response = fetch(layer, TestService::new().with_content_type("text/html")).await;
+    assert_cache_control!(response, "no-store");
+    assert!(!response.headers().contains_key("pragma"));
+}
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, TargetedField,
+};
+use tower_layer::Layer;
+
+async fn css(layer: CacheControlLayer) -> http::Response<String> {
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+    test_util::oneshot(service, test_util::get("/")).await
+}
+
+#[tokio::test]
+async fn cdn_only() {
+    let response = css(CacheControlLayer::cdn_only(Duration::from_secs(600))).await;
+    assert_cache_control!(response, "public, max-age=0, s-maxage=600");
+}
+
+#[tokio::test]
+async fn cdn_only_uses_the_default_mime_types() {
+    let layer = CacheControlLayer::cdn_only(Duration::from_secs(600));
+    let service = layer.layer(TestService::new().with_content_type("application/x-unknown"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, None);
+}
+
+#[tokio::test]
+async fn forever() {
+    let response = css(CacheControlLayer::forever()).await;
+    assert_cache_control!(response, "public, max-age=31536000, immutable");
+}
+
+#[tokio::test]
+async fn cloudflare() {
+    let layer = CacheControlLayer::cloudflare(Duration::from_secs(60), Duration::from_secs(86400));
+
+    let response = css(layer).await;
+    assert_cache_control!(response, "public, max-age=60, s-maxage=86400");
+    assert_eq!(
+        response.headers()["cloudflare-cdn-cache-control"],
+        "public, max-age=86400"
+    );
+}
+
+#[tokio::test]
+async fn cloudflare_edge_policy_can_be_replaced() {
+    let layer = CacheControlLayer::cloudflare(Duration::from_secs(60), Duration::from_secs(86400))
+        .with_targeted(TargetedField::Cloudflare, Duration::from_secs(600));
+
+    let response = css(layer).await;
+    assert_eq!(
+        response.headers()["cloudflare-cdn-cache-control"],
+        "public, max-age=600"
+    );
+}
+
+async fn fetch(layer: &CacheControlLayer, content_type: &str) -> Option<String> {
+    let service = layer
+        .clone()
+        .layer(TestService::new().with_content_type(content_type));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn static_assets() {
+    let layer = CacheControlLayer::static_assets();
+
+    for content_type in [
+        "text/css",
+        "application/javascript",
+        "application/wasm",
+        "image/avif",
+        "font/woff2",
+    ] {
+        assert_eq!(
+            fetch(&layer, content_type).await.as_deref(),
+            Some("public, max-age=31536000, immutable"),
+            "{content_type}"
+        );
+    }
+
+    assert_eq!(fetch(&layer, "text/html").await, None);
+    assert_eq!(fetch(&layer, "application/json").await, None);
+}
+
+#[tokio::test]
+async fn api() {
+    let layer = CacheControlLayer::api();
+
+    assert_eq!(
+        fetch(&layer, "application/json").await.as_deref(),
+        Some("no-store")
+    );
+    assert_eq!(fetch(&layer, "text/css").await, None);
+}
+
+#[tokio::test]
+async fn spa() {
+    let layer = CacheControlLayer::spa();
+
+    assert_eq!(
+        fetch(&layer, "text/html").await.as_deref(),
+        Some("no-cache")
+    );
+    assert_eq!(
+        fetch(&layer, "application/javascript").await.as_deref(),
+        Some("public, max-age=31536000, immutable")
+    );
+    assert_eq!(fetch(&layer, "application/json").await, None);
+}
@@ -0,0 +1,58 @@
+use axum::Router;
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, RangePolicy,
+};
+use axum_core::body::Body;
+use http::{header::RANGE, Request, StatusCode};
+use tower_http::services::ServeDir;
+use tower_layer::Layer;
+
+fn inner(status: StatusCode) -> TestService {
+    TestService::new()
+        .with_status(status)
+        .with_content_type("text/css")
+        .with_header("accept-ranges", "bytes")
+}
+
+#[tokio::test]
+async fn includes_partial_content_by_default() {
+    let service = CacheControlLayer::new().layer(inner(StatusCode::PARTIAL_CONTENT));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+}
+
+#[tokio::test]
+async fn skip_partial_only_caches_full_responses() {
+    let layer = CacheControlLayer::new().with_range_policy(RangePolicy::SkipPartial);
+
+    let full = layer.clone().layer(inner(StatusCode::OK));
+    let response = test_util::oneshot(full, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+
+    let partial = layer.layer(inner(StatusCode::PARTIAL_CONTENT));
+    let response = test_util::oneshot(partial, test_util::get("/")).await;
+    assert_cache_control!(response, None);
+}
+
+#[tokio::test]
+async fn skip_partial_with_serve_dir() {
+    let app = Router::new()
+        .nest_service("/assets", ServeDir::new("examples/assets"))
+        .layer(CacheControlLayer::new().with_range_policy(RangePolicy::SkipPartial));
+
+    let response = test_util::oneshot(app.clone(), test_util::get("/assets/style.css")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["accept-ranges"], "bytes");
+    assert_cache_control!(response, "public, max-age=31536000");
+
+    let range = Request::get("/assets/style.css")
+        .header(RANGE, "bytes=0-3")
+        .body(Body::empty())
+        .unwrap();
+    let response = test_util::oneshot(app, range).await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_cache_control!(response, None);
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher,
+};
+use http::StatusCode;
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new().with_redirect_policy(
+        Duration::from_secs(86400),
+        CacheControlDirectives::new().no_store(),
+    )
+}
+
+async fn cache_control(layer: CacheControlLayer, status: StatusCode) -> Option<String> {
+    let service = layer.layer(TestService::new().with_status(status));
+
+    let response = test_util::oneshot(service, test_util::get("/old")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn permanent_redirects() {
+    for status in [
+        StatusCode::MOVED_PERMANENTLY,
+        StatusCode::PERMANENT_REDIRECT,
+    ] {
+        assert_eq!(
+            cache_control(layer(), status).await.as_deref(),
+            Some("public, max-age=86400"),
+            "{status}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn temporary_redirects() {
+    for status in [
+        StatusCode::FOUND,
+        StatusCode::SEE_OTHER,
+        StatusCode::TEMPORARY_REDIRECT,
+    ] {
+        assert_eq!(
+            cache_control(layer(), status).await.as_deref(),
+            Some("no-store"),
+            "{status}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn other_responses_are_unaffected() {
+    assert_eq!(cache_control(layer(), StatusCode::OK).await, None);
+    assert_eq!(cache_control(layer(), StatusCode::NOT_MODIFIED).await, None);
+}
+
+#[tokio::test]
+async fn rules_take_precedence() {
+    let layer = layer().rule(Matcher::path_prefix("/old"), Duration::from_secs(60));
+
+    assert_eq!(
+        cache_control(layer, StatusCode::FOUND).await.as_deref(),
+        Some("public, max-age=60")
+    );
+}
+
+#[tokio::test]
+async fn redirects_are_left_alone_without_a_policy() {
+    assert_eq!(
+        cache_control(CacheControlLayer::new(), StatusCode::MOVED_PERMANENTLY).await,
+        None
+    );
+}
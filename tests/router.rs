@@ -0,0 +1,46 @@
+#![cfg(feature = "axum")]
+
+use axum::{
+    body::Body,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use axum_cc::{assert_cache_control, test_util, CacheControlLayer, RouterCacheControlExt};
+use http::header::CONTENT_TYPE;
+use tower_http::services::ServeDir;
+
+async fn json() -> Response {
+    ([(CONTENT_TYPE, "application/json")], "[]").into_response()
+}
+
+fn app() -> Router {
+    let api = Router::new().route("/users", get(json));
+
+    Router::new()
+        .route("/users", get(json))
+        .nest_cached("/api", api, CacheControlLayer::api())
+        .nest_service_cached("/feed", get(json), CacheControlLayer::api())
+        .cache_static("/assets", ServeDir::new("examples/assets"))
+}
+
+async fn fetch(uri: &str) -> Response<Body> {
+    test_util::oneshot(app(), test_util::get(uri)).await
+}
+
+#[tokio::test]
+async fn nested_routes_get_the_policy() {
+    assert_cache_control!(fetch("/api/users").await, "no-store");
+    assert_cache_control!(fetch("/feed").await, "no-store");
+}
+
+#[tokio::test]
+async fn sibling_routes_do_not() {
+    assert_cache_control!(fetch("/users").await, None);
+}
+
+#[tokio::test]
+async fn static_files_are_immutable() {
+    let response = fetch("/assets/style.css").await;
+    assert_cache_control!(response, "public, max-age=31536000, immutable");
+}
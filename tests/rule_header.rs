@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use axum::{routing::get, Router};
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Cached, Matcher, MimeType, OverwriteBehavior,
+};
+use http::StatusCode;
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .named_rule(
+            "api",
+            Matcher::path_prefix("/api"),
+            CacheControlDirectives::new().no_store(),
+        )
+        .rule(Matcher::path_prefix("/blog"), Duration::from_secs(60))
+        .with_policy(MimeType::HTML, CacheControlDirectives::new().no_cache())
+        .with_redirect_policy(
+            Duration::from_secs(60),
+            CacheControlDirectives::new().no_store(),
+        )
+        .with_default_policy(CacheControlDirectives::new().no_store())
+        .with_rule_header()
+}
+
+async fn rule(service: TestService, uri: &str) -> String {
+    let response = test_util::oneshot(layer().layer(service), test_util::get(uri)).await;
+    response.headers()["x-cache-control-rule"]
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[tokio::test]
+async fn the_header_names_the_rule() {
+    assert_eq!(rule(TestService::new(), "/api/users").await, "api");
+    assert_eq!(rule(TestService::new(), "/blog/post").await, "rule-1");
+}
+
+#[tokio::test]
+async fn the_header_names_other_sources() {
+    let css = || TestService::new().with_content_type("text/css");
+    let html = TestService::new().with_content_type("text/html");
+    let redirect = TestService::new().with_status(StatusCode::FOUND);
+
+    assert_eq!(rule(css(), "/").await, "mime-types");
+    assert_eq!(rule(html, "/").await, "mime-policy");
+    assert_eq!(rule(redirect, "/").await, "redirect");
+    assert_eq!(rule(TestService::new(), "/").await, "default");
+}
+
+#[tokio::test]
+async fn handler_policies() {
+    let app = Router::new()
+        .route(
+            "/api",
+            get(|| async { Cached::new(Duration::from_secs(5), "hi") }),
+        )
+        .layer(layer().with_overwrite_behavior(OverwriteBehavior::Always));
+
+    let response = test_util::oneshot(app, test_util::get("/api")).await;
+    assert_eq!(response.headers()["x-cache-control-rule"], "handler");
+}
+
+#[tokio::test]
+async fn the_header_is_opt_in() {
+    let service = CacheControlLayer::new().layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert!(!response.headers().contains_key("x-cache-control-rule"));
+}
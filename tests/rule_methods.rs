@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher, MimeType,
+};
+use axum_core::body::Body;
+use http::{Method, Request};
+use tower_layer::Layer;
+
+const METHODS: [Method; 3] = [Method::GET, Method::HEAD, Method::POST];
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::default()
+        .with_methods(&METHODS)
+        .rule(
+            Matcher::method(Method::GET).with_mime(MimeType::JSON),
+            Duration::from_secs(60),
+        )
+        .rule(
+            Matcher::method(Method::POST),
+            CacheControlDirectives::new().no_store(),
+        )
+        .rule(Matcher::mime(MimeType::HTML), Duration::from_secs(10))
+}
+
+async fn cache_control(method: Method, content_type: &str) -> Option<String> {
+    let service = layer().layer(TestService::new().with_content_type(content_type));
+    let request = Request::builder()
+        .method(method)
+        .uri("/")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = test_util::oneshot(service, request).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn rules_match_by_method_and_content_type() {
+    for (method, content_type, expected) in [
+        (Method::GET, "application/json", Some("public, max-age=60")),
+        (Method::POST, "application/json", Some("no-store")),
+        (Method::HEAD, "application/json", None),
+        (Method::GET, "text/html", Some("public, max-age=10")),
+        (Method::HEAD, "text/html", Some("public, max-age=10")),
+        (Method::POST, "text/html", Some("no-store")),
+        (Method::GET, "text/css", None),
+    ] {
+        assert_eq!(
+            cache_control(method.clone(), content_type).await.as_deref(),
+            expected,
+            "{method} {content_type}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn layer_methods_apply_before_rules() {
+    // The layer doesn't handle `PUT`, so the `any` rule never sees it.
+    let service = CacheControlLayer::default()
+        .rule(Matcher::any(), Duration::from_secs(60))
+        .layer(TestService::new());
+    let request = Request::put("/").body(Body::empty()).unwrap();
+
+    let response = test_util::oneshot(service, request).await;
+    assert_eq!(test_util::cache_control(&response), None);
+}
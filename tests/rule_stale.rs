@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher, MimeType,
+};
+use tower_layer::Layer;
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .rule(
+            Matcher::mime(MimeType::HTML),
+            CacheControlDirectives::new()
+                .public()
+                .with_max_age(secs(60))
+                .with_stale_while_revalidate(secs(30)),
+        )
+        .rule(
+            Matcher::mime(MimeType::JSON),
+            CacheControlDirectives::new()
+                .public()
+                .with_max_age(secs(10))
+                .with_stale_while_revalidate(secs(5))
+                .with_stale_if_error(secs(86400)),
+        )
+}
+
+#[tokio::test]
+async fn rules_emit_their_own_stale_windows() {
+    let html = layer().layer(TestService::new().with_content_type("text/html"));
+    let response = test_util::oneshot(html, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=60, stale-while-revalidate=30");
+
+    let json = layer().layer(TestService::new().with_content_type("application/json"));
+    let response = test_util::oneshot(json, test_util::get("/")).await;
+    assert_cache_control!(
+        response,
+        "public, max-age=10, stale-while-revalidate=5, stale-if-error=86400"
+    );
+}
+
+#[tokio::test]
+async fn layer_stale_windows_apply_outside_rules() {
+    let layer = layer().with_stale_if_error(secs(300));
+
+    let css = layer
+        .clone()
+        .layer(TestService::new().with_content_type("text/css"));
+    let response = test_util::oneshot(css, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000, stale-if-error=300");
+
+    let html = layer.layer(TestService::new().with_content_type("text/html"));
+    let response = test_util::oneshot(html, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=60, stale-while-revalidate=30");
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use axum::Router;
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, Downgrade, MimeType,
+};
+use http::StatusCode;
+use tower_http::services::ServeDir;
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .with_mime_types(&[MimeType::CSS])
+        .with_max_age(Duration::from_secs(60))
+        .with_marker_header("accept-ranges")
+}
+
+#[tokio::test]
+async fn requires_the_marker_header() {
+    let marked = layer().layer(
+        TestService::new()
+            .with_content_type("text/css")
+            .with_header("accept-ranges", "bytes"),
+    );
+    let response = test_util::oneshot(marked, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=60");
+
+    let unmarked = layer().layer(TestService::new().with_content_type("text/css"));
+    let response = test_util::oneshot(unmarked, test_util::get("/")).await;
+    assert_cache_control!(response, None);
+}
+
+#[tokio::test]
+async fn caches_files_served_by_serve_dir() {
+    let app = Router::new()
+        .nest_service("/assets", ServeDir::new("examples/assets"))
+        .layer(layer());
+
+    let response = test_util::oneshot(app, test_util::get("/assets/style.css")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_cache_control!(response, "public, max-age=60");
+}
+
+#[tokio::test]
+async fn leaves_serve_dir_fallbacks_alone() {
+    let fallback = TestService::new()
+        .with_status(StatusCode::NOT_FOUND)
+        .with_content_type("text/css");
+
+    let app = Router::new()
+        .nest_service(
+            "/assets",
+            ServeDir::new("examples/assets").fallback(fallback),
+        )
+        .layer(layer().with_error_downgrade(Downgrade::Ignore));
+
+    let response = test_util::oneshot(app, test_util::get("/assets/missing.css")).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_cache_control!(response, None);
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, Downgrade,
+};
+use tower_layer::Layer;
+
+async fn with_cookie(layer: CacheControlLayer, content_type: &str) -> http::Response<String> {
+    let service = layer.layer(
+        TestService::new()
+            .with_content_type(content_type)
+            .with_header("set-cookie", "session=abc"),
+    );
+    test_util::oneshot(service, test_util::get("/")).await
+}
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(60))
+        .with_s_maxage(Duration::from_secs(600))
+}
+
+#[tokio::test]
+async fn responses_setting_cookies_are_not_stored_by_default() {
+    assert_cache_control!(with_cookie(layer(), "text/css").await, "no-store");
+}
+
+#[tokio::test]
+async fn private_drops_s_maxage() {
+    let layer = layer().with_set_cookie_downgrade(Downgrade::Private);
+    assert_cache_control!(with_cookie(layer, "text/css").await, "private, max-age=60");
+}
+
+#[tokio::test]
+async fn skip_leaves_the_response_untouched() {
+    let layer = layer().with_set_cookie_downgrade(Downgrade::Skip);
+    assert_cache_control!(with_cookie(layer, "text/css").await, None);
+}
+
+#[tokio::test]
+async fn ignore_applies_the_policy() {
+    let layer = layer().with_set_cookie_downgrade(Downgrade::Ignore);
+    assert_cache_control!(
+        with_cookie(layer, "text/css").await,
+        "public, max-age=60, s-maxage=600"
+    );
+}
+
+#[tokio::test]
+async fn unmatched_responses_are_left_alone() {
+    assert_cache_control!(with_cookie(layer(), "text/html").await, None);
+}
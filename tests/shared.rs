@@ -0,0 +1,66 @@
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, SharedCacheControlLayer,
+};
+use axum_core::body::Body;
+use http::Request;
+use tower_layer::Layer;
+use tower_service::Service;
+
+fn css() -> TestService {
+    TestService::new().with_content_type("text/css")
+}
+
+fn no_store() -> CacheControlLayer {
+    CacheControlLayer::new().with_directives(CacheControlDirectives::new().no_store())
+}
+
+#[tokio::test]
+async fn reload_affects_built_services() {
+    let layer = SharedCacheControlLayer::new(CacheControlLayer::new());
+    let service = layer.layer(css());
+
+    let response = test_util::oneshot(service.clone(), test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+
+    layer.reload(no_store());
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "no-store");
+}
+
+#[tokio::test]
+async fn clones_share_the_configuration() {
+    let layer = SharedCacheControlLayer::new(CacheControlLayer::new());
+    let service = layer.clone().layer(css());
+
+    layer.reload(CacheControlLayer::new().with_max_age(Duration::from_secs(60)));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=60");
+}
+
+#[test]
+fn requests_in_flight_keep_their_configuration() {
+    let layer = SharedCacheControlLayer::new(CacheControlLayer::new());
+    let mut service = layer.layer(css());
+    let mut cx = Context::from_waker(Waker::noop());
+
+    assert!(Service::<Request<Body>>::poll_ready(&mut service, &mut cx).is_ready());
+    let mut future = pin!(service.call(test_util::get("/")));
+
+    layer.reload(no_store());
+
+    let Poll::Ready(Ok(response)) = future.as_mut().poll(&mut cx) else {
+        panic!("the test service responds immediately");
+    };
+    assert_cache_control!(response, "public, max-age=31536000");
+}
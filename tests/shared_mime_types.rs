@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer, MimeType,
+};
+use tower_layer::Layer;
+
+fn mime_types() -> Arc<[MimeType]> {
+    vec![MimeType::CSS, MimeType::JS].into()
+}
+
+#[test]
+fn layers_share_the_list() {
+    let mime_types = mime_types();
+    let layer = CacheControlLayer::new().with_shared_mime_types(mime_types.clone());
+
+    let clones: Vec<_> = (0..100).map(|_| layer.clone()).collect();
+    assert_eq!(Arc::strong_count(&mime_types), 102);
+
+    drop(clones);
+    assert_eq!(Arc::strong_count(&mime_types), 2);
+}
+
+#[tokio::test]
+async fn services_share_the_list() {
+    let mime_types = mime_types();
+    let layer = CacheControlLayer::new().with_shared_mime_types(mime_types.clone());
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let count = Arc::strong_count(&mime_types);
+    let clones: Vec<_> = (0..1000).map(|_| service.clone()).collect();
+
+    // Cloning a service neither copies the list nor takes a new reference to
+    // it.
+    assert_eq!(Arc::strong_count(&mime_types), count);
+
+    for service in clones.into_iter().take(3) {
+        let response = test_util::oneshot(service, test_util::get("/")).await;
+        assert_cache_control!(response, "public, max-age=31536000");
+    }
+}
@@ -0,0 +1,39 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use http::header::{HeaderName, SET_COOKIE, WWW_AUTHENTICATE};
+use tower_layer::Layer;
+
+const SKIP: [HeaderName; 2] = [SET_COOKIE, WWW_AUTHENTICATE];
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new().with_skip_if_headers(&SKIP)
+}
+
+#[tokio::test]
+async fn caches_responses_without_denied_headers() {
+    let service = layer().layer(
+        TestService::new()
+            .with_content_type("text/css")
+            .with_header("etag", "\"1\""),
+    );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+}
+
+#[tokio::test]
+async fn skips_responses_with_denied_headers() {
+    for (name, value) in [("set-cookie", "session=1"), ("www-authenticate", "Basic")] {
+        let service = layer().layer(
+            TestService::new()
+                .with_content_type("text/css")
+                .with_header(name, value),
+        );
+
+        let response = test_util::oneshot(service, test_util::get("/")).await;
+        assert_cache_control!(response, None);
+    }
+}
@@ -0,0 +1,54 @@
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use http::{header::VARY, Response};
+use tower_layer::Layer;
+
+async fn respond(content_type: &str) -> Response<String> {
+    let service = CacheControlLayer::new()
+        .with_smart_vary_encoding()
+        .layer(TestService::new().with_content_type(content_type));
+
+    test_util::oneshot(service, test_util::get("/")).await
+}
+
+#[tokio::test]
+async fn varies_compressible_types_on_accept_encoding() {
+    for content_type in ["text/css", "application/javascript", "image/svg+xml"] {
+        let response = respond(content_type).await;
+
+        assert!(test_util::cache_control(&response).is_some());
+        assert_eq!(
+            response.headers()[VARY],
+            "accept-encoding",
+            "{content_type}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn leaves_incompressible_types_alone() {
+    for content_type in ["image/png", "image/webp", "font/woff2"] {
+        let response = respond(content_type).await;
+
+        assert!(test_util::cache_control(&response).is_some());
+        assert!(!response.headers().contains_key(VARY), "{content_type}");
+    }
+}
+
+#[tokio::test]
+async fn leaves_uncached_responses_alone() {
+    let response = respond("text/plain").await;
+
+    assert_eq!(test_util::cache_control(&response), None);
+    assert!(!response.headers().contains_key(VARY));
+}
+
+#[tokio::test]
+async fn only_varies_when_enabled() {
+    let service = CacheControlLayer::new().layer(TestService::new().with_content_type("text/css"));
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert!(!response.headers().contains_key(VARY));
+}
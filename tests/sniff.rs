@@ -0,0 +1,73 @@
+#![cfg(feature = "sniff")]
+
+use axum_cc::{
+    assert_cache_control,
+    sniff::SniffLayer,
+    test_util::{self, TestService},
+    CacheControlLayer, MimeType,
+};
+use http::header::CONTENT_TYPE;
+use tower_layer::Layer;
+
+const SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg"></svg>"#;
+
+#[test]
+fn signatures() {
+    assert_eq!(
+        MimeType::sniff(b"\x89PNG\r\n\x1a\n..."),
+        Some(MimeType::PNG)
+    );
+    assert_eq!(MimeType::sniff(b"GIF89a..."), Some(MimeType::GIF));
+    assert_eq!(MimeType::sniff(b"wOF2..."), Some(MimeType::WOFF2));
+    assert_eq!(MimeType::sniff(SVG.as_bytes()), Some(MimeType::SVG));
+    assert_eq!(MimeType::sniff(b"hello"), None);
+}
+
+#[tokio::test]
+async fn sniffed_responses_get_a_content_type() {
+    let service = SniffLayer::new().layer(TestService::new().with_body(SVG));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(response.headers()[CONTENT_TYPE], "image/svg+xml");
+}
+
+#[tokio::test]
+async fn octet_streams_are_sniffed() {
+    let service = SniffLayer::new().layer(
+        TestService::new()
+            .with_content_type("application/octet-stream")
+            .with_body(SVG),
+    );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(response.headers()[CONTENT_TYPE], "image/svg+xml");
+}
+
+#[tokio::test]
+async fn other_content_types_are_kept() {
+    let service = SniffLayer::new().layer(
+        TestService::new()
+            .with_content_type("text/plain")
+            .with_body(SVG),
+    );
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/plain");
+}
+
+#[tokio::test]
+async fn unrecognized_bodies_are_left_alone() {
+    let service = SniffLayer::new().layer(TestService::new().with_body("hello"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert!(!response.headers().contains_key(CONTENT_TYPE));
+}
+
+#[tokio::test]
+async fn the_cache_control_layer_sees_the_sniffed_type() {
+    let service =
+        CacheControlLayer::new().layer(SniffLayer::new().layer(TestService::new().with_body(SVG)));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+}
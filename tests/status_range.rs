@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer, Matcher,
+};
+use http::StatusCode;
+use tower_layer::Layer;
+
+async fn cache_control(matcher: Matcher, status: StatusCode) -> Option<String> {
+    let service = CacheControlLayer::default()
+        .rule(matcher, Duration::from_secs(60))
+        .layer(TestService::new().with_status(status));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn half_open_ranges_exclude_their_end() {
+    for (status, matched) in [
+        (StatusCode::OK, true),
+        (StatusCode::NO_CONTENT, true),
+        (StatusCode::MULTIPLE_CHOICES, false),
+    ] {
+        let cache_control = cache_control(Matcher::status_range(200..300), status).await;
+        assert_eq!(cache_control.is_some(), matched, "{status}");
+    }
+}
+
+#[tokio::test]
+async fn inclusive_ranges_include_their_end() {
+    for (status, matched) in [
+        (StatusCode::OK, true),
+        (StatusCode::PERMANENT_REDIRECT, true),
+        (StatusCode::BAD_REQUEST, false),
+        (StatusCode::NOT_FOUND, false),
+    ] {
+        let cache_control = cache_control(Matcher::status_range(..=399), status).await;
+        assert_eq!(cache_control.is_some(), matched, "{status}");
+    }
+}
+
+#[tokio::test]
+#[allow(clippy::reversed_empty_ranges)]
+async fn empty_ranges_match_nothing() {
+    for range in [(300..300), (0..0), (400..200)] {
+        for status in [StatusCode::OK, StatusCode::MULTIPLE_CHOICES] {
+            let cache_control = cache_control(Matcher::status_range(range.clone()), status).await;
+            assert_eq!(cache_control, None, "{range:?} {status}");
+        }
+    }
+
+    let cache_control = cache_control(Matcher::status_range(..0), StatusCode::OK).await;
+    assert_eq!(cache_control, None);
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, CacheTagHeader, Matcher, TargetedField,
+};
+use tower_layer::Layer;
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::default().rule_with_surrogate_keys(
+        Matcher::path_prefix("/products"),
+        Duration::from_secs(60),
+        |req, _| {
+            let id = req.uri.path().rsplit('/').next().unwrap_or_default();
+            vec!["products".to_owned(), format!("product-{id}")]
+        },
+    )
+}
+
+async fn fetch(layer: CacheControlLayer, uri: &str) -> http::Response<String> {
+    test_util::oneshot(layer.layer(TestService::new()), test_util::get(uri)).await
+}
+
+#[tokio::test]
+async fn keys_come_from_the_request() {
+    let response = fetch(layer(), "/products/42").await;
+    assert_cache_control!(response, "public, max-age=60");
+    assert_eq!(response.headers()["surrogate-key"], "products product-42");
+}
+
+#[tokio::test]
+async fn other_paths_get_no_keys() {
+    let response = fetch(layer(), "/about").await;
+    assert_cache_control!(response, None);
+    assert!(!response.headers().contains_key("surrogate-key"));
+}
+
+#[tokio::test]
+async fn keys_are_added_to_the_layer_tags() {
+    let layer = layer().with_cache_tags(CacheTagHeader::SurrogateKey, &["site"]);
+
+    let response = fetch(layer, "/products/42").await;
+    assert_eq!(
+        response.headers()["surrogate-key"],
+        "site products product-42"
+    );
+}
+
+#[tokio::test]
+async fn invalid_keys_are_dropped() {
+    let layer = CacheControlLayer::default().rule_with_surrogate_keys(
+        Matcher::any(),
+        Duration::from_secs(60),
+        |_, _| vec!["bad\nkey".to_owned()],
+    );
+
+    let response = fetch(layer, "/").await;
+    assert_cache_control!(response, "public, max-age=60");
+    assert!(!response.headers().contains_key("surrogate-key"));
+}
+
+#[tokio::test]
+async fn keys_with_spaces_are_dropped() {
+    let layer = CacheControlLayer::default().rule_with_surrogate_keys(
+        Matcher::any(),
+        Duration::from_secs(60),
+        |_, _| {
+            vec![
+                "products".to_owned(),
+                "product 42".to_owned(),
+                String::new(),
+            ]
+        },
+    );
+
+    let response = fetch(layer, "/").await;
+    assert_eq!(response.headers()["surrogate-key"], "products");
+}
+
+#[tokio::test]
+async fn surrogate_control() {
+    let layer = CacheControlLayer::new().with_targeted(
+        TargetedField::SurrogateControl,
+        CacheControlDirectives::new().with_max_age(Duration::from_secs(86400)),
+    );
+    let service = layer.layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+    assert_eq!(response.headers()["surrogate-control"], "max-age=86400");
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Downgrade, MimeType, TargetedField,
+};
+use http::HeaderName;
+use tower_layer::Layer;
+
+const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+fn layer() -> CacheControlLayer {
+    CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(60))
+        .with_targeted(TargetedField::Cdn, DAY)
+}
+
+async fn fetch(layer: CacheControlLayer, service: TestService) -> http::Response<String> {
+    test_util::oneshot(layer.layer(service), test_util::get("/")).await
+}
+
+fn css() -> TestService {
+    TestService::new().with_content_type("text/css")
+}
+
+#[tokio::test]
+async fn targeted_fields_are_set_alongside_cache_control() {
+    let response = fetch(layer(), css()).await;
+    assert_cache_control!(response, "public, max-age=60");
+    assert_eq!(
+        response.headers()["cdn-cache-control"],
+        "public, max-age=86400"
+    );
+}
+
+#[tokio::test]
+async fn vendor_and_custom_fields() {
+    let layer = layer()
+        .with_targeted(TargetedField::Cloudflare, DAY)
+        .with_targeted(TargetedField::Akamai, DAY)
+        .with_targeted(TargetedField::Vercel, DAY)
+        .with_targeted(
+            TargetedField::Custom(HeaderName::from_static("edge-cache-control")),
+            DAY,
+        );
+
+    let response = fetch(layer, css()).await;
+    for name in [
+        "cdn-cache-control",
+        "cloudflare-cdn-cache-control",
+        "akamai-cache-control",
+        "vercel-cdn-cache-control",
+        "edge-cache-control",
+    ] {
+        assert_eq!(response.headers()[name], "public, max-age=86400", "{name}");
+    }
+}
+
+#[tokio::test]
+async fn setting_a_field_again_replaces_it() {
+    let layer = layer().with_targeted(TargetedField::Cdn, Duration::from_secs(600));
+
+    let response = fetch(layer, css()).await;
+    assert_eq!(
+        response.headers()["cdn-cache-control"],
+        "public, max-age=600"
+    );
+}
+
+#[tokio::test]
+async fn uncacheable_responses_get_no_targeted_fields() {
+    let no_store = layer().with_policy(MimeType::HTML, CacheControlDirectives::new().no_store());
+    let response = fetch(no_store, TestService::new().with_content_type("text/html")).await;
+    assert_cache_control!(response, "no-store");
+    assert!(!response.headers().contains_key("cdn-cache-control"));
+
+    let private = layer().with_set_cookie_downgrade(Downgrade::Private);
+    let response = fetch(private, css().with_header("set-cookie", "session=abc")).await;
+    assert_cache_control!(response, "private, max-age=60");
+    assert!(!response.headers().contains_key("cdn-cache-control"));
+}
+
+#[tokio::test]
+async fn unmatched_responses_get_no_targeted_fields() {
+    let response = fetch(layer(), TestService::new().with_content_type("text/html")).await;
+    assert_cache_control!(response, None);
+    assert!(!response.headers().contains_key("cdn-cache-control"));
+}
@@ -0,0 +1,32 @@
+use axum_cc::{
+    assert_cache_control,
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use http::StatusCode;
+use tower_layer::Layer;
+
+#[tokio::test]
+async fn test_service_responds_as_configured() {
+    let service = TestService::new()
+        .with_status(StatusCode::CREATED)
+        .with_content_type("text/plain")
+        .with_header("x-request-id", "42")
+        .with_body("hello");
+
+    let response = test_util::oneshot(service, test_util::get("/")).await;
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(response.headers()["content-type"], "text/plain");
+    assert_eq!(response.headers()["x-request-id"], "42");
+    assert_eq!(response.body(), "hello");
+    assert_cache_control!(response, None);
+}
+
+#[tokio::test]
+async fn oneshot_drives_a_layered_service() {
+    let service = CacheControlLayer::new().layer(TestService::new().with_content_type("text/css"));
+
+    let response = test_util::oneshot(service, test_util::get("/style.css")).await;
+    assert_cache_control!(response, "public, max-age=31536000");
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlDirectives, CacheControlLayer, Matcher, MimeType,
+};
+use http::header::{ACCEPT, ACCEPT_LANGUAGE, VARY};
+use tower_layer::Layer;
+
+async fn vary(layer: CacheControlLayer, inner: TestService, uri: &str) -> Vec<String> {
+    let response = test_util::oneshot(layer.layer(inner), test_util::get(uri)).await;
+
+    response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap().to_owned())
+        .collect()
+}
+
+fn html() -> TestService {
+    TestService::new().with_content_type("text/html")
+}
+
+#[tokio::test]
+async fn layer_names_are_appended_to_existing_values() {
+    let layer = CacheControlLayer::new().with_vary(&[ACCEPT, ACCEPT_LANGUAGE]);
+    let inner = TestService::new()
+        .with_content_type("text/css")
+        .with_header("vary", "origin");
+
+    assert_eq!(
+        vary(layer, inner, "/").await,
+        ["origin, accept, accept-language"]
+    );
+}
+
+#[tokio::test]
+async fn rule_names_only_apply_to_matched_responses() {
+    let layer = CacheControlLayer::default()
+        .rule_with_vary(
+            Matcher::path_prefix("/docs"),
+            Duration::from_secs(60),
+            &[ACCEPT_LANGUAGE],
+        )
+        .rule(Matcher::any(), Duration::from_secs(60));
+
+    assert_eq!(
+        vary(layer.clone(), html(), "/docs/intro").await,
+        ["accept-language"]
+    );
+    assert!(vary(layer, html(), "/blog").await.is_empty());
+}
+
+#[tokio::test]
+async fn policy_names_only_apply_to_their_policy() {
+    let layer = CacheControlLayer::new()
+        .with_policy(MimeType::HTML, CacheControlDirectives::new().no_cache())
+        .with_policy_vary(MimeType::HTML, &[ACCEPT_LANGUAGE]);
+
+    assert_eq!(vary(layer.clone(), html(), "/").await, ["accept-language"]);
+
+    let css = TestService::new().with_content_type("text/css");
+    assert!(vary(layer.clone(), css, "/").await.is_empty());
+
+    // A rule picks the policy instead, so the policy's names don't apply.
+    let layer = layer.rule(Matcher::path_prefix("/blog"), Duration::from_secs(60));
+    assert!(vary(layer, html(), "/blog").await.is_empty());
+}
+
+#[tokio::test]
+async fn layer_and_rule_names_combine() {
+    let layer = CacheControlLayer::default()
+        .with_vary(&[ACCEPT])
+        .rule_with_vary(Matcher::any(), Duration::from_secs(60), &[ACCEPT_LANGUAGE]);
+
+    assert_eq!(vary(layer, html(), "/").await, ["accept, accept-language"]);
+}
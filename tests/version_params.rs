@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use axum_cc::{
+    test_util::{self, TestService},
+    CacheControlLayer,
+};
+use tower_layer::Layer;
+
+async fn cache_control(content_type: &str, uri: &str) -> Option<String> {
+    let service = CacheControlLayer::new()
+        .with_max_age(Duration::from_secs(3600))
+        .with_version_params(&["v", "hash"])
+        .layer(TestService::new().with_content_type(content_type));
+
+    let response = test_util::oneshot(service, test_util::get(uri)).await;
+    test_util::cache_control(&response).map(ToOwned::to_owned)
+}
+
+#[tokio::test]
+async fn versioned_urls_are_immutable() {
+    for uri in ["/app.js?v=3", "/app.js?hash=3fa9c2", "/app.js?lang=en&v=3"] {
+        assert_eq!(
+            cache_control("application/javascript", uri)
+                .await
+                .as_deref(),
+            Some("public, max-age=31536000, immutable"),
+            "{uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn other_urls_keep_the_policy() {
+    for uri in ["/app.js", "/app.js?v=", "/app.js?v", "/app.js?version=3"] {
+        assert_eq!(
+            cache_control("application/javascript", uri)
+                .await
+                .as_deref(),
+            Some("public, max-age=3600"),
+            "{uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn unmatched_responses_are_left_alone() {
+    assert_eq!(cache_control("text/html", "/index.html?v=3").await, None);
+}